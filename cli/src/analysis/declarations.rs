@@ -0,0 +1,165 @@
+//! Index of the names declared by each module.
+
+use super::{Diagnostic, Location};
+use crate::parsing::syntax_tree::{ResourceClass, Statement};
+use crate::project::Project;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Declarations only clash with declarations of the same namespace.
+///
+/// Resources, data sources, variables, outputs and module instances are all
+/// values: they are looked up by the same bare name, so they share one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    Value,
+    Provider,
+    Type,
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value => write!(f, "value"),
+            Self::Provider => write!(f, "provider"),
+            Self::Type => write!(f, "type"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Declaration<'a> {
+    pub module: &'a [String],
+    pub namespace: Namespace,
+    pub name: &'a str,
+    /// Keyword of the declaring statement, e.g. `resource` or `variable`.
+    pub keyword: String,
+    pub location: Location,
+}
+
+#[derive(Debug, Default)]
+pub struct DeclarationIndex<'a> {
+    pub declarations: Vec<Declaration<'a>>,
+}
+
+impl<'a> DeclarationIndex<'a> {
+    pub fn build(project: &'a Project<'a>) -> DeclarationIndex<'a> {
+        let mut declarations = Vec::new();
+        for module in project.modules() {
+            for file in &module.files {
+                for statement in &file.tree.statements {
+                    let (namespace, name, keyword) = match statement {
+                        Statement::Resource(data) => {
+                            let namespace = match data.class {
+                                ResourceClass::Provider => Namespace::Provider,
+                                _ => Namespace::Value,
+                            };
+                            (namespace, data.name.value.as_ref(), data.class.to_string())
+                        }
+                        Statement::Type(data) => (
+                            Namespace::Type,
+                            data.type_name.as_ref(),
+                            String::from("type"),
+                        ),
+                        _ => continue,
+                    };
+                    declarations.push(Declaration {
+                        module: &module.path,
+                        namespace,
+                        name,
+                        keyword,
+                        location: Location::new(file, statement.span()),
+                    });
+                }
+            }
+        }
+        DeclarationIndex { declarations }
+    }
+
+    pub fn get(
+        &self,
+        module: &[String],
+        namespace: Namespace,
+        name: &str,
+    ) -> Vec<&Declaration<'a>> {
+        self.declarations
+            .iter()
+            .filter(|x| x.module == module && x.namespace == namespace && x.name == name)
+            .collect()
+    }
+
+    /// Reports every declaration whose name is already taken in the same
+    /// module and namespace, pointing at the first declaration as well.
+    pub fn duplicates(&self) -> Vec<Diagnostic> {
+        let mut first: HashMap<_, &Declaration> = HashMap::new();
+        let mut diagnostics = Vec::new();
+        for declaration in &self.declarations {
+            let key = (declaration.module, declaration.namespace, declaration.name);
+            let original: &Declaration = match first.get(&key) {
+                Some(&original) => original,
+                None => {
+                    first.insert(key, declaration);
+                    continue;
+                }
+            };
+            let message = format!(
+                "{} `{}` is already declared as {} {} `{}` at {}",
+                declaration.keyword,
+                declaration.name,
+                article(&original.keyword),
+                original.keyword,
+                original.name,
+                original.location
+            );
+            diagnostics.push(Diagnostic {
+                location: declaration.location.clone(),
+                message: format!("duplicate {} name: {message}", declaration.namespace),
+            });
+        }
+        diagnostics
+    }
+}
+
+fn article(word: &str) -> &'static str {
+    match word.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        true => "an",
+        false => "a",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    fn duplicates(files: &[(&str, &str)]) -> Vec<String> {
+        let sources = Sources::from_files(files);
+        let project = Project::parse(&sources).unwrap();
+        let index = DeclarationIndex::build(&project);
+        index.duplicates().iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn reports_duplicates_across_files() {
+        let files = [
+            ("main.land", "resource db of azure::mssql_server {}\n"),
+            ("outputs.land", "\noutput db {\n  value = 1\n}\n"),
+        ];
+        assert_eq!(
+            duplicates(&files),
+            ["project/outputs.land:2:1: duplicate value name: output `db` is already declared as a resource `db` at project/main.land:1:1"]
+        );
+    }
+
+    #[test]
+    fn keeps_namespaces_and_modules_apart() {
+        let files = [
+            (
+                "main.land",
+                "type db of string;\nprovider db of azure {}\nresource db of azure::mssql_server {}\n",
+            ),
+            ("network/main.land", "resource db of azure::mssql_server {}\n"),
+        ];
+        assert!(duplicates(&files).is_empty());
+    }
+}
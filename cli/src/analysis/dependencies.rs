@@ -0,0 +1,459 @@
+//! Deployment order of the declarations of a project.
+//!
+//! A declaration depends on another when it refers to it in an attribute,
+//! when it lists it in `@depends_on(...)`, or when it follows a `scoped`
+//! declaration of the same file that it is deployed into, such as the
+//! resource group of an Azure resource.
+
+use super::{Diagnostic, Location};
+use crate::parsing::syntax_tree::{Expression, ObjectPath, ResourceClass, ResourceData};
+use crate::project::instances::{Expansion, InstanceDeclaration};
+use crate::project::resolve::Resolution;
+use crate::project::{Module, Project};
+use crate::providers;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    Reference,
+    Explicit,
+    Scope,
+}
+
+/// `from` depends on `to`, so `to` is deployed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug)]
+pub struct DependencyGraph<'a> {
+    pub nodes: Vec<InstanceDeclaration<'a>>,
+    pub edges: Vec<Edge>,
+}
+
+impl<'a> DependencyGraph<'a> {
+    pub fn build(project: &'a Project<'a>, expansion: &Expansion<'a>) -> DependencyGraph<'a> {
+        let mut graph = DependencyGraph {
+            nodes: expansion.declarations.clone(),
+            edges: Vec::new(),
+        };
+        let index = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (key(&node.instance, node.data), i))
+            .collect::<HashMap<_, _>>();
+        let resolver = Resolver { project, index };
+        let mut edges = Vec::new();
+
+        for (from, node) in graph.nodes.iter().enumerate() {
+            let data = node.data;
+            let attributes = data.body.iter().flat_map(|body| body.values());
+            let nested = data.nested_attributes().into_iter().map(|(_, x)| x);
+            for expression in attributes.chain(nested).flat_map(Expression::walk) {
+                let target = resolver.target(node.module, &node.instance, expression);
+                // Blocks such as `validation` may refer to their declaration.
+                let target = target.filter(|to| *to != from);
+                edges.extend(target.map(|to| (from, to, EdgeKind::Reference)));
+            }
+            let dependencies = data.annotation("depends_on").map(|x| x.arguments.iter());
+            for expression in dependencies.into_iter().flatten() {
+                let target = resolver.target(node.module, &node.instance, expression);
+                edges.extend(target.map(|to| (from, to, EdgeKind::Explicit)));
+            }
+        }
+
+        // Inputs of a module instance take the values assigned by the
+        // instance declaration, which are resolved in the enclosing module.
+        for instance in &expansion.instances {
+            let (_, parent) = instance.path.split_last().unwrap();
+            let parent_module = match project.declaring_module(instance.declaration) {
+                Some(module) => module,
+                None => continue,
+            };
+            for (name, expression) in instance.inputs().into_iter().flatten() {
+                let input = instance.module.inputs().find(|x| x.name == *name);
+                let from = match input.and_then(|x| resolver.node(&instance.path, x)) {
+                    Some(from) => from,
+                    None => continue,
+                };
+                for expression in expression.walk() {
+                    let target = resolver.target(parent_module, parent, expression);
+                    edges.extend(target.map(|to| (from, to, EdgeKind::Reference)));
+                }
+            }
+        }
+
+        for (from, node) in graph.nodes.iter().enumerate() {
+            for to in resolver.scopes(node) {
+                edges.push((from, to, EdgeKind::Scope));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for (from, to, kind) in edges {
+            let edge = Edge { from, to, kind };
+            if seen.insert(edge) {
+                graph.edges.push(edge);
+            }
+        }
+        graph
+    }
+
+    pub fn dependencies(&self, node: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == node)
+    }
+
+    /// Nodes ordered so that every node comes after its dependencies, or the
+    /// cycles preventing such an order.
+    pub fn topological_order(&self) -> Result<Vec<usize>, Vec<Vec<usize>>> {
+        let mut pending = vec![0; self.nodes.len()];
+        for edge in &self.edges {
+            pending[edge.from] += 1;
+        }
+        let mut ready = (0..self.nodes.len())
+            .filter(|&node| pending[node] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::new();
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for edge in self.edges.iter().filter(|edge| edge.to == node) {
+                pending[edge.from] -= 1;
+                if pending[edge.from] == 0 {
+                    ready.push_back(edge.from);
+                }
+            }
+        }
+        match order.len() == self.nodes.len() {
+            true => Ok(order),
+            false => Err(self.cycles()),
+        }
+    }
+
+    /// One cycle per group of mutually dependent nodes, as the path of nodes
+    /// followed until coming back to the first one.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut cycles = Vec::new();
+        for component in self.strongly_connected_components() {
+            let start = component[0];
+            let is_cycle =
+                component.len() > 1 || self.dependencies(start).any(|edge| edge.to == start);
+            if is_cycle {
+                cycles.extend(self.cycle_through(start, &component));
+            }
+        }
+        cycles
+    }
+
+    /// Cycle diagnostics, located at the first declaration of each cycle.
+    pub fn check(&self) -> Vec<Diagnostic> {
+        self.cycles()
+            .into_iter()
+            .map(|cycle| {
+                let names = cycle
+                    .iter()
+                    .chain(cycle.first())
+                    .map(|&node| format!("`{}`", self.nodes[node].qualified_name()))
+                    .collect::<Vec<_>>();
+                let node = &self.nodes[cycle[0]];
+                Diagnostic {
+                    location: location(node),
+                    message: format!("dependency cycle: {}", names.join(" -> ")),
+                }
+            })
+            .collect()
+    }
+
+    /// Shortest path from `start` back to itself within `component`.
+    fn cycle_through(&self, start: usize, component: &[usize]) -> Option<Vec<usize>> {
+        let mut previous = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for edge in self.dependencies(node) {
+                if !component.contains(&edge.to) {
+                    continue;
+                }
+                if edge.to == start {
+                    let mut path = vec![node];
+                    while let Some(&before) = previous.get(path.last().unwrap()) {
+                        path.push(before);
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if let Entry::Vacant(entry) = previous.entry(edge.to) {
+                    entry.insert(node);
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+        None
+    }
+
+    /// Tarjan's algorithm; each component lists its nodes in ascending order.
+    fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            next: 0,
+            indices: vec![None; self.nodes.len()],
+            lowlinks: vec![0; self.nodes.len()],
+            stack: Vec::new(),
+            on_stack: vec![false; self.nodes.len()],
+            components: Vec::new(),
+        };
+        for node in 0..self.nodes.len() {
+            if tarjan.indices[node].is_none() {
+                tarjan.visit(node);
+            }
+        }
+        let mut components = tarjan.components;
+        for component in &mut components {
+            component.sort();
+        }
+        components.sort();
+        components
+    }
+}
+
+struct Tarjan<'g, 'a> {
+    graph: &'g DependencyGraph<'a>,
+    next: usize,
+    indices: Vec<Option<usize>>,
+    lowlinks: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_, '_> {
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.next);
+        self.lowlinks[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        let targets = self
+            .graph
+            .dependencies(node)
+            .map(|x| x.to)
+            .collect::<Vec<_>>();
+        for target in targets {
+            match self.indices[target] {
+                None => {
+                    self.visit(target);
+                    self.lowlinks[node] = self.lowlinks[node].min(self.lowlinks[target]);
+                }
+                Some(index) if self.on_stack[target] => {
+                    self.lowlinks[node] = self.lowlinks[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.lowlinks[node]) == self.indices[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+struct Resolver<'a> {
+    project: &'a Project<'a>,
+    index: HashMap<(Vec<String>, usize), usize>,
+}
+
+impl<'a> Resolver<'a> {
+    /// The node of a declaration as seen from `instance`: the one of the
+    /// innermost enclosing instance, or the one outside of any instance.
+    fn node(&self, instance: &[String], data: &ResourceData) -> Option<usize> {
+        (0..=instance.len())
+            .rev()
+            .find_map(|length| self.index.get(&key(&instance[..length], data)))
+            .copied()
+    }
+
+    fn target(
+        &self,
+        module: &'a Module<'a>,
+        instance: &[String],
+        expression: &Expression,
+    ) -> Option<usize> {
+        let (path, members) = match expression {
+            Expression::Literal(_) | Expression::List(_) | Expression::Call(_) => return None,
+            Expression::Reference(path) => (path, None),
+            Expression::Object(ObjectPath {
+                object,
+                member_path,
+            }) => (object, member_path.as_deref()),
+        };
+        let data = match self.project.resolve(module, path) {
+            Ok(Resolution::Declaration(_, data)) => data,
+            _ => return None,
+        };
+        if data.class != ResourceClass::Module {
+            return self.node(instance, data);
+        }
+
+        // `shop.url` is the output `url` of the module instance `shop`.
+        let member = members.and_then(|x| x.first())?;
+        let path = [instance.to_vec(), vec![data.name.to_string()]].concat();
+        let target = match self.project.resolve(module, &data.type_name) {
+            Ok(Resolution::Module(target)) => target,
+            _ => return None,
+        };
+        let output = target.outputs().find(|x| x.name == *member)?;
+        self.index.get(&key(&path, output)).copied()
+    }
+
+    /// Scoped declarations written before the node in the same file that it
+    /// is deployed into.
+    fn scopes(&self, node: &InstanceDeclaration) -> Vec<usize> {
+        let file = match node.module.source_file(node.data) {
+            Some(file) => file,
+            None => return Vec::new(),
+        };
+        let parents = providers::scope_parents(&file.tree.statements, node.data);
+        let parents = parents.into_iter();
+        parents
+            .filter_map(|(_, parent)| self.node(&node.instance, parent))
+            .collect()
+    }
+}
+
+fn key(instance: &[String], data: &ResourceData) -> (Vec<String>, usize) {
+    (instance.to_vec(), data as *const _ as usize)
+}
+
+fn location(node: &InstanceDeclaration) -> Location {
+    match node.module.source_file(node.data) {
+        Some(file) => Location::new(file, node.data.span),
+        None => Location {
+            path: node.module.dir.clone(),
+            line: 1,
+            column: 1,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    fn with_graph(sources: &Sources, check: impl Fn(&DependencyGraph)) {
+        let project = Project::parse(sources).unwrap();
+        let expansion = project.expand();
+        check(&DependencyGraph::build(&project, &expansion));
+    }
+
+    fn names(graph: &DependencyGraph, nodes: &[usize]) -> Vec<String> {
+        let nodes = nodes.iter().map(|&node| graph.nodes[node].qualified_name());
+        nodes.collect()
+    }
+
+    #[test]
+    fn orders_dependencies_first() {
+        let sources = Sources::from_files(&[(
+            "main.land",
+            r#"
+resource app of azure::windows_webapp {
+  name = plan.name
+}
+
+resource plan of azure::service_plan scoped {
+  name = "plan"
+}
+
+@depends_on(plan)
+resource db of azure::mssql_server {
+  name = "db"
+}
+"#,
+        )]);
+        with_graph(&sources, |graph| {
+            let order = names(graph, &graph.topological_order().unwrap());
+            let position = |name: &str| order.iter().position(|x| x == name).unwrap();
+            assert!(position("plan") < position("app"));
+            assert!(position("plan") < position("db"));
+        });
+    }
+
+    #[test]
+    fn reports_the_cycle_path() {
+        let sources = Sources::from_files(&[(
+            "main.land",
+            r#"
+resource a of azure::storage_account {
+  name = c.name
+}
+
+resource b of azure::storage_account {
+  name = a.name
+}
+
+resource c of azure::storage_account {
+  name = b.name
+}
+
+resource d of azure::storage_account {
+  name = a.name
+}
+"#,
+        )]);
+        with_graph(&sources, |graph| {
+            let cycles = graph.topological_order().unwrap_err();
+            assert_eq!(cycles.len(), 1);
+            assert_eq!(names(graph, &cycles[0]), ["a", "c", "b"]);
+            let messages = graph.check().into_iter().map(|x| x.message);
+            assert_eq!(
+                messages.collect::<Vec<_>>(),
+                ["dependency cycle: `a` -> `c` -> `b` -> `a`"]
+            );
+        });
+    }
+
+    #[test]
+    fn only_links_scopes_the_type_is_deployed_into() {
+        let sources = Sources::from_files(&[(
+            "main.land",
+            r#"
+resource rg of azure::resource_group scoped {
+  name = "rg"
+}
+
+resource kv of azure::key_vault scoped {
+  name = "kv"
+  tenant_id = webapp.name
+}
+
+resource webapp of azure::windows_webapp {
+  name = "webapp"
+}
+"#,
+        )]);
+        with_graph(&sources, |graph| {
+            assert!(graph.cycles().is_empty());
+            let webapp = graph
+                .nodes
+                .iter()
+                .position(|x| x.data.name.value == "webapp");
+            let scopes = graph
+                .dependencies(webapp.unwrap())
+                .filter(|edge| edge.kind == EdgeKind::Scope)
+                .map(|edge| graph.nodes[edge.to].qualified_name());
+            assert_eq!(scopes.collect::<Vec<_>>(), ["rg"]);
+        });
+    }
+}
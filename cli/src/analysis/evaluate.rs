@@ -0,0 +1,756 @@
+//! Evaluation of expressions to the values they take when deployed.
+//!
+//! Literals evaluate to themselves. A reference to a variable evaluates to
+//! the input given to the module instance or to the deployment, or else to
+//! the variable default, and a reference to an output to its `value`.
+//! Attributes of resources are known when written in the resource body;
+//! anything only decided by the cloud, such as ids, is [`Value::Unknown`]
+//! until the deployment is applied.
+//!
+//! Values of `sensitive = true` variables and outputs, and of attributes the
+//! provider schema marks as sensitive, are wrapped in [`Value::Sensitive`],
+//! and so is anything computed from them. Sensitive values are redacted when
+//! formatted, so they can be used in checks but never show up in messages.
+
+use super::functions;
+use super::sensitivity::{is_marked_sensitive, schema_sensitivity, REDACTED};
+use crate::inputs::VariableValues;
+use crate::parsing::syntax_tree::{
+    Expression, Literal, NameIdentifier, Number, ObjectPath, ReferencePath, ResourceClass,
+    ResourceData,
+};
+use crate::project::instances::{Expansion, InstanceDeclaration};
+use crate::project::resolve::{Resolution, ResolveError};
+use crate::project::{Module, Project};
+use crate::providers::schema::{AttributeType, SchemaRegistry};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+    Object(BTreeMap<String, Value>),
+    /// A value only known once the deployment is applied.
+    Unknown,
+    /// A value that must not be shown, such as a password.
+    Sensitive(Box<Value>),
+}
+
+impl Value {
+    /// The value, marked as sensitive.
+    pub fn sensitive(self) -> Value {
+        match self {
+            Self::Sensitive(_) => self,
+            value => Self::Sensitive(Box::new(value)),
+        }
+    }
+
+    /// Whether the value, or a part of it, is sensitive.
+    pub fn is_sensitive(&self) -> bool {
+        match self {
+            Self::Sensitive(_) => true,
+            Self::List(items) => items.iter().any(Value::is_sensitive),
+            Self::Map(entries) | Self::Object(entries) => entries.values().any(Value::is_sensitive),
+            _ => false,
+        }
+    }
+
+    /// The value without its sensitivity markers, for computations. It must
+    /// not be shown.
+    pub fn revealed(&self) -> Value {
+        match self {
+            Self::Sensitive(value) => value.revealed(),
+            Self::List(items) => Self::List(items.iter().map(Value::revealed).collect()),
+            Self::Map(entries) => Self::Map(reveal_entries(entries)),
+            Self::Object(fields) => Self::Object(reveal_entries(fields)),
+            value => value.clone(),
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        match self {
+            Self::Unknown => false,
+            Self::Sensitive(value) => value.is_known(),
+            Self::List(items) => items.iter().all(Value::is_known),
+            Self::Map(entries) | Self::Object(entries) => entries.values().all(Value::is_known),
+            _ => true,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            Self::Sensitive(value) => value.as_str(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            Self::Sensitive(value) => value.as_bool(),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            Self::Sensitive(value) => value.as_number(),
+            _ => None,
+        }
+    }
+
+    /// The most precise type describing the value.
+    pub fn value_type(&self) -> AttributeType {
+        match self {
+            Self::String(_) => AttributeType::String,
+            Self::Number(_) => AttributeType::Number,
+            Self::Bool(_) => AttributeType::Bool,
+            Self::List(items) => {
+                AttributeType::Tuple(items.iter().map(Value::value_type).collect())
+            }
+            Self::Map(entries) => {
+                let mut types = entries.values().map(Value::value_type);
+                let first = types.next().unwrap_or(AttributeType::Dynamic);
+                match types.all(|x| x == first) {
+                    true => AttributeType::Map(Box::new(first)),
+                    false => AttributeType::Map(Box::new(AttributeType::Dynamic)),
+                }
+            }
+            Self::Object(fields) => AttributeType::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.value_type()))
+                    .collect(),
+            ),
+            Self::Unknown => AttributeType::Dynamic,
+            Self::Sensitive(value) => value.value_type(),
+        }
+    }
+
+    /// The value of a field of an object or map.
+    pub fn member(&self, name: &str) -> Option<Value> {
+        match self {
+            Self::Map(entries) | Self::Object(entries) => entries.get(name).cloned(),
+            Self::Unknown => Some(Self::Unknown),
+            Self::Sensitive(value) => value.member(name).map(Value::sensitive),
+            _ => None,
+        }
+    }
+}
+
+impl From<&Literal<'_>> for Value {
+    fn from(literal: &Literal) -> Value {
+        match literal {
+            Literal::Boolean(value) => Value::Bool(*value),
+            Literal::String(value) => Value::String(value.to_string()),
+            Literal::Number(Number::Integer(value) | Number::FloatingPoint(value)) => {
+                value.parse().map(Value::Number).unwrap_or(Value::Unknown)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => write!(f, "{value:?}"),
+            Self::Number(value) => write!(f, "{value}"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::List(items) => {
+                let items = items.iter().map(Value::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Self::Map(entries) | Self::Object(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(name, value)| format!("{name} = {value}"))
+                    .collect::<Vec<_>>();
+                write!(f, "{{ {} }}", entries.join(", "))
+            }
+            Self::Unknown => write!(f, "(known after apply)"),
+            Self::Sensitive(_) => write!(f, "{REDACTED}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EvaluationError {
+    Resolve(ResolveError),
+    NotAValue(String),
+    NoMember { value: String, member: String },
+    Cycle(String),
+    UnknownFunction(String),
+    Function(String),
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Resolve(error) => write!(f, "{error}"),
+            Self::NotAValue(path) => write!(f, "`{path}` is a module, not a value"),
+            Self::NoMember { value, member } => write!(f, "`{value}` has no member `{member}`"),
+            Self::Cycle(name) => write!(f, "the value of `{name}` depends on itself"),
+            Self::UnknownFunction(name) => write!(f, "unknown function `{name}`"),
+            Self::Function(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// A module as seen from one of its instances.
+#[derive(Debug, Clone, Copy)]
+struct Frame<'a> {
+    module: &'a Module<'a>,
+    /// The `module` statement of the instance, assigning the inputs.
+    instance: Option<&'a ResourceData<'a>>,
+}
+
+/// Where an expression is evaluated: a module, and the chain of module
+/// instances it is deployed through, outermost first.
+#[derive(Debug, Clone)]
+pub struct Scope<'a> {
+    frames: Vec<Frame<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    /// A module deployed on its own, whose variables take their defaults.
+    pub fn module(module: &'a Module<'a>) -> Scope<'a> {
+        let frames = vec![Frame {
+            module,
+            instance: None,
+        }];
+        Scope { frames }
+    }
+
+    /// The module of an expanded declaration, seen through its instances.
+    pub fn instance(
+        project: &'a Project<'a>,
+        expansion: &Expansion<'a>,
+        declaration: &InstanceDeclaration<'a>,
+    ) -> Scope<'a> {
+        let instances = (1..=declaration.instance.len()).filter_map(|length| {
+            let path = &declaration.instance[..length];
+            expansion.instances.iter().find(|x| x.path == path)
+        });
+        let mut frames = Vec::new();
+        for instance in instances {
+            if frames.is_empty() {
+                match project.declaring_module(instance.declaration) {
+                    Some(module) => frames.push(Frame {
+                        module,
+                        instance: None,
+                    }),
+                    None => break,
+                }
+            }
+            frames.push(Frame {
+                module: instance.module,
+                instance: Some(instance.declaration),
+            });
+        }
+        match frames.last() {
+            Some(frame) if std::ptr::eq(frame.module, declaration.module) => Scope { frames },
+            _ => Scope::module(declaration.module),
+        }
+    }
+
+    fn current(&self) -> Frame<'a> {
+        *self.frames.last().unwrap()
+    }
+
+    fn outer(&self) -> Scope<'a> {
+        let frames = self.frames[..self.frames.len() - 1].to_vec();
+        Scope { frames }
+    }
+
+    fn enter(&self, module: &'a Module<'a>, instance: &'a ResourceData<'a>) -> Scope<'a> {
+        let mut frames = self.frames.clone();
+        frames.push(Frame {
+            module,
+            instance: Some(instance),
+        });
+        Scope { frames }
+    }
+}
+
+pub struct Evaluator<'a> {
+    project: &'a Project<'a>,
+    inputs: Option<&'a VariableValues>,
+    schemas: Option<&'a SchemaRegistry>,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(project: &'a Project<'a>) -> Evaluator<'a> {
+        Evaluator {
+            project,
+            inputs: None,
+            schemas: None,
+        }
+    }
+
+    /// Gives the variables of modules deployed on their own these values
+    /// instead of their defaults.
+    pub fn with_inputs(mut self, inputs: &'a VariableValues) -> Evaluator<'a> {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    /// Marks the attributes these schemas call sensitive as such.
+    pub fn with_schemas(mut self, schemas: &'a SchemaRegistry) -> Evaluator<'a> {
+        self.schemas = Some(schemas);
+        self
+    }
+
+    /// Evaluates an expression written in `module`.
+    pub fn evaluate(
+        &self,
+        module: &'a Module<'a>,
+        expression: &Expression,
+    ) -> Result<Value, EvaluationError> {
+        self.evaluate_in(&Scope::module(module), expression)
+    }
+
+    pub fn evaluate_in(
+        &self,
+        scope: &Scope<'a>,
+        expression: &Expression,
+    ) -> Result<Value, EvaluationError> {
+        self.expression(scope, expression, &mut Vec::new())
+    }
+
+    /// The value of an attribute of a declaration written in the scope, or
+    /// `None` when the attribute is not set.
+    pub fn attribute(
+        &self,
+        scope: &Scope<'a>,
+        data: &'a ResourceData<'a>,
+        name: &str,
+    ) -> Option<Result<Value, EvaluationError>> {
+        let expression = data.attribute(name)?;
+        Some(self.expression(scope, expression, &mut vec![data]))
+    }
+
+    fn expression(
+        &self,
+        scope: &Scope<'a>,
+        expression: &Expression,
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<Value, EvaluationError> {
+        let (path, members): (_, &[NameIdentifier]) = match expression {
+            Expression::Literal(literal) => return Ok(Value::from(literal)),
+            Expression::List(items) => {
+                let items = items.iter().map(|x| self.expression(scope, x, stack));
+                return items.collect::<Result<_, _>>().map(Value::List);
+            }
+            Expression::Call(call) => {
+                let function = functions::get(&call.function.value)
+                    .ok_or_else(|| EvaluationError::UnknownFunction(call.function.to_string()))?;
+                let arguments = call.arguments.iter();
+                let arguments = arguments
+                    .map(|x| self.expression(scope, x, stack))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return function.call(&arguments).map_err(EvaluationError::Function);
+            }
+            Expression::Reference(path) => (path, &[]),
+            Expression::Object(ObjectPath {
+                object,
+                member_path,
+            }) => (object, member_path.as_deref().unwrap_or_default()),
+        };
+        self.reference(scope, path, members, stack)
+    }
+
+    fn reference(
+        &self,
+        scope: &Scope<'a>,
+        path: &ReferencePath,
+        members: &[NameIdentifier],
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<Value, EvaluationError> {
+        let current = scope.current().module;
+        let (module, data) = match self.project.resolve(current, path) {
+            Ok(Resolution::Declaration(module, data)) => (module, data),
+            Ok(Resolution::Module(_)) => return Err(EvaluationError::NotAValue(path.to_string())),
+            Err(error) => return Err(EvaluationError::Resolve(error)),
+        };
+        // Declarations of other modules are seen as deployed on their own.
+        let scope = match std::ptr::eq(module, current) {
+            true => scope.clone(),
+            false => Scope::module(module),
+        };
+
+        if stack.iter().any(|x| std::ptr::eq(*x, data)) {
+            return Err(EvaluationError::Cycle(data.name.to_string()));
+        }
+        stack.push(data);
+        let result = self.declaration(&scope, data, members, stack);
+        stack.pop();
+
+        let (mut value, rest) = result?;
+        let mut name = match members.len() - rest.len() {
+            0 => data.name.to_string(),
+            used => format!("{}.{}", data.name, join(&members[..used])),
+        };
+        for member in rest {
+            value = value
+                .member(&member.value)
+                .ok_or(EvaluationError::NoMember {
+                    value: name.clone(),
+                    member: member.to_string(),
+                })?;
+            name = format!("{name}.{member}");
+        }
+        Ok(value)
+    }
+
+    /// The value of a declaration, along with the members left to look up
+    /// in it.
+    fn declaration<'m>(
+        &self,
+        scope: &Scope<'a>,
+        data: &'a ResourceData<'a>,
+        members: &'m [NameIdentifier<'m>],
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<(Value, &'m [NameIdentifier<'m>]), EvaluationError> {
+        match data.class {
+            ResourceClass::Variable => Ok((self.variable(scope, data, stack)?, members)),
+            ResourceClass::Output => Ok((self.output_value(scope, data, stack)?, members)),
+            ResourceClass::Module => self.instance(scope, data, members, stack),
+            ResourceClass::Custom | ResourceClass::Data => {
+                self.resource(scope, data, members, stack)
+            }
+            ResourceClass::Provider => Ok((Value::Unknown, members)),
+        }
+    }
+
+    /// Attributes written in the body of a resource are known; those of
+    /// data sources, and anything else, once deployed. A resource as a
+    /// whole is sensitive as soon as one of its attributes is.
+    fn resource<'m>(
+        &self,
+        scope: &Scope<'a>,
+        data: &'a ResourceData<'a>,
+        members: &'m [NameIdentifier<'m>],
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<(Value, &'m [NameIdentifier<'m>]), EvaluationError> {
+        let (first, rest) = match members.split_first() {
+            Some(split) => split,
+            None => {
+                let mut attributes = data.body.iter().flat_map(|body| body.iter());
+                let is_sensitive = attributes.any(|(name, expression)| {
+                    self.is_schema_sensitive(data, &name.value)
+                        || self
+                            .expression(scope, expression, stack)
+                            .is_ok_and(|x| x.is_sensitive())
+                });
+                return match is_sensitive {
+                    true => Ok((Value::Unknown.sensitive(), members)),
+                    false => Ok((Value::Unknown, members)),
+                };
+            }
+        };
+        let value = match data.attribute(&first.value) {
+            Some(expression) if data.class == ResourceClass::Custom => {
+                self.expression(scope, expression, stack)?
+            }
+            _ => Value::Unknown,
+        };
+        match self.is_schema_sensitive(data, &first.value) {
+            true => Ok((value.sensitive(), rest)),
+            false => Ok((value, rest)),
+        }
+    }
+
+    fn is_schema_sensitive(&self, data: &ResourceData, name: &str) -> bool {
+        self.schemas
+            .is_some_and(|x| schema_sensitivity(x, data, name) == Some(true))
+    }
+
+    fn variable(
+        &self,
+        scope: &Scope<'a>,
+        data: &'a ResourceData<'a>,
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<Value, EvaluationError> {
+        let value = self.variable_value(scope, data, stack)?;
+        match is_marked_sensitive(data) {
+            true => Ok(value.sensitive()),
+            false => Ok(value),
+        }
+    }
+
+    fn variable_value(
+        &self,
+        scope: &Scope<'a>,
+        data: &'a ResourceData<'a>,
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<Value, EvaluationError> {
+        let frame = scope.current();
+        let input = match frame.instance {
+            Some(instance) => instance.attribute(&data.name.value),
+            None => {
+                let input = self
+                    .inputs
+                    .and_then(|x| x.get(frame.module, &data.name.value));
+                if let Some(value) = input {
+                    return Ok(value.clone());
+                }
+                None
+            }
+        };
+        if let Some(expression) = input {
+            return self.expression(&scope.outer(), expression, stack);
+        }
+        match data.attribute("default") {
+            Some(expression) => self.expression(scope, expression, stack),
+            None => Ok(Value::Unknown),
+        }
+    }
+
+    /// A module instance is an object of its outputs.
+    fn instance<'m>(
+        &self,
+        scope: &Scope<'a>,
+        data: &'a ResourceData<'a>,
+        members: &'m [NameIdentifier<'m>],
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<(Value, &'m [NameIdentifier<'m>]), EvaluationError> {
+        let target = match self
+            .project
+            .resolve(scope.current().module, &data.type_name)
+        {
+            Ok(Resolution::Module(target)) => target,
+            _ => return Ok((Value::Unknown, &[])),
+        };
+        let inner = scope.enter(target, data);
+
+        if let Some((first, rest)) = members.split_first() {
+            let output = target.outputs().find(|x| x.name.value == first.value);
+            let output = output.ok_or_else(|| EvaluationError::NoMember {
+                value: data.name.to_string(),
+                member: first.to_string(),
+            })?;
+            let value = self.output(&inner, output, stack)?;
+            return Ok((value, rest));
+        }
+
+        let mut fields = BTreeMap::new();
+        for output in target.outputs() {
+            let value = self.output(&inner, output, stack)?;
+            fields.insert(output.name.to_string(), value);
+        }
+        Ok((Value::Object(fields), members))
+    }
+
+    fn output(
+        &self,
+        scope: &Scope<'a>,
+        output: &'a ResourceData<'a>,
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<Value, EvaluationError> {
+        if stack.iter().any(|x| std::ptr::eq(*x, output)) {
+            return Err(EvaluationError::Cycle(output.name.to_string()));
+        }
+        stack.push(output);
+        let result = self.output_value(scope, output, stack);
+        stack.pop();
+        result
+    }
+
+    fn output_value(
+        &self,
+        scope: &Scope<'a>,
+        output: &'a ResourceData<'a>,
+        stack: &mut Vec<&'a ResourceData<'a>>,
+    ) -> Result<Value, EvaluationError> {
+        let value = match output.attribute("value") {
+            Some(expression) => self.expression(scope, expression, stack)?,
+            None => Value::Unknown,
+        };
+        match is_marked_sensitive(output) {
+            true => Ok(value.sensitive()),
+            false => Ok(value),
+        }
+    }
+}
+
+fn join(names: &[NameIdentifier]) -> String {
+    let names = names.iter().map(|x| x.value.as_ref()).collect::<Vec<_>>();
+    names.join(".")
+}
+
+fn reveal_entries(entries: &BTreeMap<String, Value>) -> BTreeMap<String, Value> {
+    let entries = entries.iter();
+    entries
+        .map(|(name, x)| (name.clone(), x.revealed()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::Inputs;
+    use crate::project::Sources;
+
+    const SCHEMA: &str = r#"{
+  "provider_schemas": {
+    "registry.terraform.io/hashicorp/azurerm": {
+      "resource_schemas": {
+        "azurerm_storage_account": {
+          "block": {
+            "attributes": {
+              "name": { "type": "string", "required": true },
+              "primary_access_key": { "type": "string", "computed": true, "sensitive": true }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+    const MODULE: &str = r#"
+variable name of string { default = "db" }
+
+output name {
+  value = upper(name)
+}
+"#;
+
+    /// The values of the outputs of the root module, with the variables set
+    /// by `flags`.
+    fn outputs(source: &str, flags: &[&str]) -> Vec<String> {
+        let files = [("main.land", source), ("modules/db/main.land", MODULE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let mut inputs = Inputs::new();
+        for flag in flags {
+            inputs.load_flag(flag).unwrap();
+        }
+        let inputs = inputs.resolve(&project).unwrap();
+        let schemas = SchemaRegistry::from_json(SCHEMA);
+        let evaluator = Evaluator::new(&project)
+            .with_inputs(&inputs)
+            .with_schemas(&schemas);
+        let root = project.module(&[]).unwrap();
+        let scope = Scope::module(root);
+        root.outputs()
+            .map(
+                |output| match evaluator.attribute(&scope, output, "value") {
+                    Some(Ok(value)) => format!("{} = {value}", output.name),
+                    Some(Err(error)) => format!("{}: {error}", output.name),
+                    None => format!("{}: not set", output.name),
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn gives_inputs_precedence_over_defaults() {
+        let source = r#"
+variable sku of string { default = "S1" }
+variable tier of string { default = "Basic" }
+variable location of string;
+
+output sku { value = sku }
+output tier { value = tier }
+output location { value = location }
+"#;
+        assert_eq!(
+            outputs(source, &["tier=Premium"]),
+            [
+                "sku = \"S1\"",
+                "tier = \"Premium\"",
+                "location = (known after apply)",
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluates_outputs_of_each_module_instance() {
+        let source = r#"
+module shop of land::modules::db { name = "shop" }
+module blog of land::modules::db {}
+
+output shop { value = shop.name }
+output blog { value = blog }
+"#;
+        assert_eq!(
+            outputs(source, &[]),
+            ["shop = \"SHOP\"", "blog = { name = \"DB\" }"]
+        );
+    }
+
+    #[test]
+    fn propagates_sensitive_values() {
+        let source = r#"
+variable secret of string {
+  default   = "hunter2"
+  sensitive = true
+}
+
+module shop of land::modules::db { name = secret }
+
+output length { value = length(secret) }
+output shop { value = shop.name }
+output list { value = ["public", secret] }
+"#;
+        assert_eq!(
+            outputs(source, &[]),
+            [
+                format!("length = {REDACTED}"),
+                format!("shop = {REDACTED}"),
+                format!("list = [\"public\", {REDACTED}]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_schema_sensitive_attributes() {
+        let source = r#"
+resource storage of azure::storage_account {
+  name = "storage"
+}
+
+resource plan of azure::service_plan {
+  name = storage.primary_access_key
+}
+
+output name { value = storage.name }
+output key { value = storage.primary_access_key }
+output storage { value = storage }
+output plan { value = plan }
+"#;
+        assert_eq!(
+            outputs(source, &[]),
+            [
+                String::from("name = \"storage\""),
+                format!("key = {REDACTED}"),
+                String::from("storage = (known after apply)"),
+                format!("plan = {REDACTED}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_cycles_and_unknown_functions() {
+        let source = r#"
+output first { value = second }
+output second { value = first }
+output shout { value = shout("x") }
+"#;
+        assert_eq!(
+            outputs(source, &[]),
+            [
+                "first: the value of `first` depends on itself",
+                "second: the value of `second` depends on itself",
+                "shout: unknown function `shout`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Builtin functions callable in expressions, such as
+//! `matches(name, "^[a-z0-9-]{3,24}$")`.
+
+use super::evaluate::Value;
+use crate::providers::schema::AttributeType as Type;
+
+#[derive(Debug)]
+pub struct Function {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Types of the arguments; the last one repeats for variadic functions.
+    pub parameters: &'static [Type],
+    pub is_variadic: bool,
+    pub result: Type,
+    evaluate: fn(&[Value]) -> Result<Value, String>,
+}
+
+impl Function {
+    pub fn check_arity(&self, count: usize) -> Result<(), String> {
+        let expected = self.parameters.len();
+        match self.is_variadic {
+            true if count >= expected => Ok(()),
+            false if count == expected => Ok(()),
+            true => Err(format!(
+                "function `{}` takes at least {expected} arguments, found {count}",
+                self.name
+            )),
+            false => Err(format!(
+                "function `{}` takes {expected} arguments, found {count}",
+                self.name
+            )),
+        }
+    }
+
+    /// The expected type of an argument.
+    pub fn parameter(&self, index: usize) -> &Type {
+        let last = self.parameters.len().saturating_sub(1);
+        self.parameters
+            .get(index.min(last))
+            .unwrap_or(&Type::Dynamic)
+    }
+
+    /// Calls the function. The result is unknown as soon as one of the
+    /// arguments is, and sensitive as soon as one of them is.
+    pub fn call(&self, arguments: &[Value]) -> Result<Value, String> {
+        self.check_arity(arguments.len())?;
+        let is_sensitive = arguments.iter().any(Value::is_sensitive);
+        if !arguments.iter().all(Value::is_known) {
+            return match is_sensitive {
+                true => Ok(Value::Unknown.sensitive()),
+                false => Ok(Value::Unknown),
+            };
+        }
+        let arguments = arguments.iter().map(Value::revealed).collect::<Vec<_>>();
+        let result = (self.evaluate)(&arguments);
+        match (result, is_sensitive) {
+            (Ok(value), true) => Ok(value.sensitive()),
+            (Ok(value), false) => Ok(value),
+            // Errors may quote the arguments.
+            (Err(_), true) => Err(format!("function `{}` failed", self.name)),
+            (Err(error), false) => Err(format!("function `{}`: {error}", self.name)),
+        }
+    }
+}
+
+pub const FUNCTIONS: &[Function] = &[
+    Function {
+        name: "and",
+        description: "Whether all of the arguments are true",
+        parameters: &[Type::Bool],
+        is_variadic: true,
+        result: Type::Bool,
+        evaluate: |arguments| {
+            let values = arguments
+                .iter()
+                .map(boolean)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Bool(values.into_iter().all(|x| x)))
+        },
+    },
+    Function {
+        name: "contains",
+        description: "Whether a list contains a value",
+        parameters: &[Type::Dynamic, Type::Dynamic],
+        is_variadic: false,
+        result: Type::Bool,
+        evaluate: |arguments| match &arguments[0] {
+            Value::List(items) => Ok(Value::Bool(items.contains(&arguments[1]))),
+            other => Err(format!("expected a list, found {}", other.value_type())),
+        },
+    },
+    Function {
+        name: "ends_with",
+        description: "Whether a string ends with a suffix",
+        parameters: &[Type::String, Type::String],
+        is_variadic: false,
+        result: Type::Bool,
+        evaluate: |arguments| {
+            let (text, suffix) = (string(&arguments[0])?, string(&arguments[1])?);
+            Ok(Value::Bool(text.ends_with(suffix)))
+        },
+    },
+    Function {
+        name: "equal",
+        description: "Whether two values are equal",
+        parameters: &[Type::Dynamic, Type::Dynamic],
+        is_variadic: false,
+        result: Type::Bool,
+        evaluate: |arguments| Ok(Value::Bool(arguments[0] == arguments[1])),
+    },
+    Function {
+        name: "length",
+        description: "Number of characters of a string, or of items of a list or map",
+        parameters: &[Type::Dynamic],
+        is_variadic: false,
+        result: Type::Number,
+        evaluate: |arguments| {
+            let length = match &arguments[0] {
+                Value::String(value) => value.chars().count(),
+                Value::List(items) => items.len(),
+                Value::Map(entries) | Value::Object(entries) => entries.len(),
+                other => return Err(format!("{} values have no length", other.value_type())),
+            };
+            Ok(Value::Number(length as f64))
+        },
+    },
+    Function {
+        name: "lower",
+        description: "A string in lowercase",
+        parameters: &[Type::String],
+        is_variadic: false,
+        result: Type::String,
+        evaluate: |arguments| Ok(Value::String(string(&arguments[0])?.to_lowercase())),
+    },
+    Function {
+        name: "matches",
+        description: "Whether a string matches a regular expression",
+        parameters: &[Type::String, Type::String],
+        is_variadic: false,
+        result: Type::Bool,
+        evaluate: |arguments| {
+            let (text, pattern) = (string(&arguments[0])?, string(&arguments[1])?);
+            let pattern = regex::Regex::new(pattern)
+                .map_err(|_| format!("invalid regular expression `{pattern}`"))?;
+            Ok(Value::Bool(pattern.is_match(text)))
+        },
+    },
+    Function {
+        name: "not",
+        description: "The negation of a bool",
+        parameters: &[Type::Bool],
+        is_variadic: false,
+        result: Type::Bool,
+        evaluate: |arguments| Ok(Value::Bool(!boolean(&arguments[0])?)),
+    },
+    Function {
+        name: "or",
+        description: "Whether any of the arguments is true",
+        parameters: &[Type::Bool],
+        is_variadic: true,
+        result: Type::Bool,
+        evaluate: |arguments| {
+            let values = arguments
+                .iter()
+                .map(boolean)
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Bool(values.into_iter().any(|x| x)))
+        },
+    },
+    Function {
+        name: "starts_with",
+        description: "Whether a string starts with a prefix",
+        parameters: &[Type::String, Type::String],
+        is_variadic: false,
+        result: Type::Bool,
+        evaluate: |arguments| {
+            let (text, prefix) = (string(&arguments[0])?, string(&arguments[1])?);
+            Ok(Value::Bool(text.starts_with(prefix)))
+        },
+    },
+    Function {
+        name: "upper",
+        description: "A string in uppercase",
+        parameters: &[Type::String],
+        is_variadic: false,
+        result: Type::String,
+        evaluate: |arguments| Ok(Value::String(string(&arguments[0])?.to_uppercase())),
+    },
+];
+
+pub fn get(name: &str) -> Option<&'static Function> {
+    FUNCTIONS.iter().find(|x| x.name == name)
+}
+
+fn string(value: &Value) -> Result<&str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("expected a string, found {}", value.value_type()))
+}
+
+fn boolean(value: &Value) -> Result<bool, String> {
+    value
+        .as_bool()
+        .ok_or_else(|| format!("expected a bool, found {}", value.value_type()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn checks_the_number_of_arguments() {
+        let and = get("and").unwrap();
+        assert_eq!(
+            and.call(&[Value::Bool(true), Value::Bool(false)]),
+            Ok(Value::Bool(false))
+        );
+        assert_eq!(
+            and.call(&[]),
+            Err(String::from(
+                "function `and` takes at least 1 arguments, found 0"
+            ))
+        );
+        let upper = get("upper").unwrap();
+        assert_eq!(
+            upper.call(&[text("a"), text("b")]),
+            Err(String::from("function `upper` takes 1 arguments, found 2"))
+        );
+        assert_eq!(and.parameter(3), &Type::Bool);
+    }
+
+    #[test]
+    fn keeps_unknown_and_sensitive_arguments() {
+        let lower = get("lower").unwrap();
+        assert_eq!(lower.call(&[Value::Unknown]), Ok(Value::Unknown));
+        assert_eq!(
+            lower.call(&[Value::Unknown.sensitive()]),
+            Ok(Value::Unknown.sensitive())
+        );
+        assert_eq!(
+            lower.call(&[text("SECRET").sensitive()]),
+            Ok(text("secret").sensitive())
+        );
+        let contains = get("contains").unwrap();
+        let list = Value::List(vec![text("F1"), text("B1")]);
+        assert_eq!(
+            contains.call(&[list, text("B1").sensitive()]),
+            Ok(Value::Bool(true).sensitive())
+        );
+    }
+
+    #[test]
+    fn hides_sensitive_arguments_in_errors() {
+        let matches = get("matches").unwrap();
+        assert_eq!(
+            matches.call(&[text("name"), text("[")]),
+            Err(String::from(
+                "function `matches`: invalid regular expression `[`"
+            ))
+        );
+        assert_eq!(
+            matches.call(&[text("name"), text("[").sensitive()]),
+            Err(String::from("function `matches` failed"))
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Checks that every name used by a declaration refers to something.
+
+use super::symbols::{Symbol, SymbolTable};
+use super::{functions, suggest, with_suggestion, Diagnostic};
+use crate::parsing::syntax_tree::{
+    Expression, ObjectPath, Reference, ReferencePath, ResourceClass, Statement,
+};
+use crate::project::resolve::{Resolution, ResolveError};
+use crate::project::{Module, Project};
+use crate::providers;
+use crate::providers::schema::{SchemaKind, SchemaRegistry};
+
+/// Reports resource types and references that cannot be resolved, with a
+/// suggestion when a known name is close to the one written.
+pub fn check(
+    project: &Project,
+    symbols: &SymbolTable,
+    schemas: &SchemaRegistry,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let namespaces = symbols.provider_namespaces();
+
+    for symbol in &symbols.symbols {
+        let data = symbol.data;
+        if matches!(data.class, ResourceClass::Custom | ResourceClass::Data) {
+            if let Some(message) = check_type(project, symbols, schemas, &namespaces, symbol) {
+                diagnostics.push(diagnostic(symbol, message));
+            }
+        }
+
+        let dependencies = data.annotation("depends_on").map(|x| x.arguments.iter());
+        for expression in dependencies
+            .into_iter()
+            .flatten()
+            .flat_map(Expression::walk)
+        {
+            if let Some(message) = check_expression(project, symbols, symbol, expression) {
+                let message = format!("dependency of `{}`: {message}", data.name);
+                diagnostics.push(diagnostic(symbol, message));
+            }
+        }
+
+        let attributes = data.body.iter().flatten();
+        let mut attributes = attributes
+            .map(|(name, value)| (name.to_string(), value))
+            .collect::<Vec<_>>();
+        attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        attributes.extend(data.nested_attributes());
+        for (attribute, expression) in attributes {
+            let expressions = expression.walk().into_iter();
+            let mut messages =
+                expressions.filter_map(|x| check_expression(project, symbols, symbol, x));
+            if let Some(message) = messages.next() {
+                let message = format!("attribute `{attribute}` of `{}`: {message}", data.name);
+                diagnostics.push(diagnostic(symbol, message));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn diagnostic(symbol: &Symbol, message: String) -> Diagnostic {
+    Diagnostic {
+        location: symbol.location(),
+        message,
+    }
+}
+
+fn kind_name(kind: SchemaKind) -> &'static str {
+    match kind {
+        SchemaKind::Resource => "resource",
+        SchemaKind::DataSource => "data source",
+    }
+}
+
+fn check_type(
+    project: &Project,
+    symbols: &SymbolTable,
+    schemas: &SchemaRegistry,
+    namespaces: &[String],
+    symbol: &Symbol,
+) -> Option<String> {
+    let path = &symbol.data.type_name;
+    if let [Reference::Name(namespace), Reference::Name(name)] = path.sequence.as_slice() {
+        if namespaces.iter().any(|x| x == namespace.value.as_ref()) {
+            let kind = match symbol.data.class {
+                ResourceClass::Data => SchemaKind::DataSource,
+                _ => SchemaKind::Resource,
+            };
+            let builtin = match kind {
+                SchemaKind::Resource => providers::resource_types(&namespace.value),
+                SchemaKind::DataSource => None,
+            };
+            let types = match schemas.type_names(kind, &namespace.value) {
+                Some(types) => types,
+                None => match builtin {
+                    Some(types) => types.to_vec(),
+                    None => return None,
+                },
+            };
+            if types.contains(&name.value.as_ref()) {
+                return None;
+            }
+            let message = format!("unknown {} type `{path}`", kind_name(kind));
+            let suggestion =
+                suggest(&name.value, types.iter().copied()).map(|x| format!("{namespace}::{x}"));
+            return Some(with_suggestion(message, suggestion.as_deref()));
+        }
+    }
+
+    match project.resolve(symbol.module, path) {
+        Ok(_) => None,
+        Err(error @ ResolveError::Unresolved { .. }) => {
+            let mut candidates = candidates(project, symbols, symbol.module, path);
+            if path.sequence.len() > 1 {
+                candidates.extend(namespaces.iter().cloned());
+            }
+            let failed = unresolved_name(&error);
+            let suggestion = suggest(failed, candidates.iter().map(String::as_str));
+            let message = format!("unknown resource type `{path}`: {error}");
+            Some(with_suggestion(message, suggestion))
+        }
+        Err(error) => Some(format!("unknown resource type `{path}`: {error}")),
+    }
+}
+
+fn check_expression(
+    project: &Project,
+    symbols: &SymbolTable,
+    symbol: &Symbol,
+    expression: &Expression,
+) -> Option<String> {
+    let (path, members) = match expression {
+        Expression::Literal(_) | Expression::List(_) => return None,
+        Expression::Call(call) => {
+            let name = &call.function.value;
+            if functions::get(name).is_some() {
+                return None;
+            }
+            let message = format!("unknown function `{name}`");
+            let suggestion = suggest(name, functions::FUNCTIONS.iter().map(|x| x.name));
+            return Some(with_suggestion(message, suggestion));
+        }
+        Expression::Reference(path) => (path, None),
+        Expression::Object(ObjectPath {
+            object,
+            member_path,
+        }) => (object, member_path.as_deref()),
+    };
+
+    match project.resolve(symbol.module, path) {
+        Ok(Resolution::Module(module)) => Some(format!(
+            "`{path}` is the module {}, not a value",
+            module.display_path()
+        )),
+        // Members of variables and resources are checked against their type
+        // by the type checks, only module outputs are names.
+        Ok(Resolution::Declaration(module, data)) => {
+            let member = members.and_then(|x| x.first())?;
+            if data.class != ResourceClass::Module {
+                return None;
+            }
+            let target = match project.resolve(module, &data.type_name) {
+                Ok(Resolution::Module(target)) => target,
+                _ => return None,
+            };
+            if target.outputs().any(|output| output.name == *member) {
+                return None;
+            }
+            let message = format!("module instance `{path}` has no output `{member}`");
+            let outputs = target.outputs().map(|x| x.name.value.as_ref());
+            let suggestion = suggest(&member.value, outputs);
+            Some(with_suggestion(message, suggestion))
+        }
+        Err(error @ ResolveError::Unresolved { .. }) => {
+            let candidates = candidates(project, symbols, symbol.module, path);
+            let failed = unresolved_name(&error);
+            let suggestion = suggest(failed, candidates.iter().map(String::as_str));
+            Some(with_suggestion(error.to_string(), suggestion))
+        }
+        Err(error) => Some(error.to_string()),
+    }
+}
+
+fn unresolved_name(error: &ResolveError) -> &str {
+    match error {
+        ResolveError::Unresolved { name, .. } => name,
+        _ => "",
+    }
+}
+
+/// Names visible where the lookup of `path` failed: the declarations and
+/// child modules of the deepest module the path reaches, and the imports of
+/// `from` when the very first name is the unknown one.
+fn candidates(
+    project: &Project,
+    symbols: &SymbolTable,
+    from: &Module,
+    path: &ReferencePath,
+) -> Vec<String> {
+    let mut scope_module = from;
+    let mut is_first = true;
+    for length in 1..path.sequence.len() {
+        let prefix = ReferencePath {
+            sequence: path.sequence[..length].to_vec(),
+        };
+        match project.resolve(from, &prefix) {
+            Ok(Resolution::Module(module)) => {
+                scope_module = module;
+                is_first = false;
+            }
+            _ => break,
+        }
+    }
+
+    let declarations = symbols
+        .in_module(scope_module)
+        .map(|x| x.name().to_string());
+    let children = scope_module.children.iter().map(|x| x.name.clone());
+    let mut names = declarations.chain(children).collect::<Vec<_>>();
+    if is_first {
+        let imports = from.statements().filter_map(|statement| match statement {
+            Statement::Use(data) => data.imported_name().map(|x| x.to_string()),
+            _ => None,
+        });
+        names.extend(imports);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    const DATABASE: &str = r#"
+variable server_name of string;
+
+output server_id {
+  value = server_name
+}
+"#;
+
+    fn messages(source: &str) -> Vec<String> {
+        let files = [("main.land", source), ("modules/db/main.land", DATABASE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let symbols = SymbolTable::build(&project);
+        let schemas = SchemaRegistry::new();
+        let diagnostics = check(&project, &symbols, &schemas).into_iter();
+        diagnostics.map(|x| x.message).collect()
+    }
+
+    #[test]
+    fn suggests_close_resource_types() {
+        let source = r#"
+provider main of azure {}
+
+resource rg of azure::resource_grup {}
+"#;
+        assert_eq!(
+            messages(source),
+            ["unknown resource type `azure::resource_grup` (did you mean `azure::resource_group`?)"]
+        );
+    }
+
+    #[test]
+    fn suggests_close_references_and_functions() {
+        let source = r#"
+provider main of azure {}
+
+variable location of string;
+
+resource rg of azure::resource_group {
+  name     = lower(locaiton)
+  location = lowr(location)
+}
+"#;
+        assert_eq!(
+            messages(source),
+            [
+                "attribute `location` of `rg`: unknown function `lowr` (did you mean `lower`?)",
+                "attribute `name` of `rg`: cannot find `locaiton` in land (did you mean `location`?)"
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_outputs_of_module_instances() {
+        let source = r#"
+provider main of azure {}
+
+module db of land::modules::db { server_name = "db" }
+
+resource rg of azure::resource_group {
+  name = db.server_ids
+}
+"#;
+        assert_eq!(
+            messages(source),
+            ["attribute `name` of `rg`: module instance `db` has no output `server_ids` (did you mean `server_id`?)"]
+        );
+    }
+
+    #[test]
+    fn checks_dependencies() {
+        let source = r#"
+provider main of azure {}
+
+@depends_on(missing)
+resource rg of azure::resource_group {}
+"#;
+        assert_eq!(
+            messages(source),
+            ["dependency of `rg`: cannot find `missing` in land"]
+        );
+    }
+}
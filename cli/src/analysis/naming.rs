@@ -0,0 +1,148 @@
+//! Checks of resource names against the naming rules of their provider.
+
+use super::evaluate::{Evaluator, Scope};
+use super::sensitivity::REDACTED;
+use super::{Diagnostic, Location};
+use crate::inputs::VariableValues;
+use crate::parsing::syntax_tree::ResourceClass;
+use crate::project::instances::Expansion;
+use crate::project::Project;
+use crate::providers::naming;
+use crate::providers::schema::SchemaRegistry;
+
+#[derive(Debug, Default)]
+pub struct NamingReport {
+    pub errors: Vec<Diagnostic>,
+    /// Reminders that a name must be unique across the whole cloud.
+    pub hints: Vec<Diagnostic>,
+}
+
+/// Checks the `name` attribute of every resource with a known naming rule,
+/// when the name is known before deployment, for every instance of the
+/// module declaring it. Names computed from sensitive values are not shown.
+pub fn check<'a>(
+    project: &'a Project<'a>,
+    expansion: &Expansion<'a>,
+    schemas: &'a SchemaRegistry,
+    inputs: &'a VariableValues,
+) -> NamingReport {
+    let evaluator = Evaluator::new(project)
+        .with_inputs(inputs)
+        .with_schemas(schemas);
+    let mut report = NamingReport::default();
+    for declaration in &expansion.declarations {
+        let data = declaration.data;
+        if data.class != ResourceClass::Custom {
+            continue;
+        }
+        let rule = match naming::rule_for(&data.type_name.to_string()) {
+            Some(rule) => rule,
+            None => continue,
+        };
+        let expression = match data.attribute("name") {
+            Some(expression) => expression,
+            None => continue,
+        };
+        let file = match declaration.module.source_file(data) {
+            Some(file) => file,
+            None => continue,
+        };
+        let scope = Scope::instance(project, expansion, declaration);
+        let value = match evaluator.evaluate_in(&scope, expression) {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let name = match value.as_str() {
+            Some(name) => name,
+            None => continue,
+        };
+        let is_sensitive = value.is_sensitive();
+        let shown = match is_sensitive {
+            true => REDACTED,
+            false => name,
+        };
+
+        for problem in rule.check(name, is_sensitive) {
+            report.errors.push(Diagnostic {
+                location: Location::new(file, data.span),
+                message: format!(
+                    "name `{shown}` of {} `{}`: `{}` names {problem}",
+                    data.class,
+                    declaration.qualified_name(),
+                    rule.resource_type
+                ),
+            });
+        }
+        if rule.globally_unique {
+            report.hints.push(Diagnostic {
+                location: Location::new(file, data.span),
+                message: format!(
+                    "`{}` names are globally unique, so `{shown}` may already be taken by someone else",
+                    rule.resource_type
+                ),
+            });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    const DATABASE: &str = r#"
+variable server_name of string;
+
+resource server of azure::mssql_server {
+  name = server_name
+}
+"#;
+
+    fn report(source: &str) -> NamingReport {
+        let files = [("main.land", source), ("modules/db/main.land", DATABASE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let inputs = VariableValues::default();
+        check(&project, &expansion, &schemas, &inputs)
+    }
+
+    #[test]
+    fn checks_names_given_by_module_instances() {
+        let report = report(
+            r#"
+module shop of land::modules::db { server_name = "Shop_SERVER" }
+module blog of land::modules::db { server_name = "blog-server" }
+"#,
+        );
+        let messages = report.errors.iter().map(|x| x.message.as_str());
+        let messages = messages.collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3, "{messages:?}");
+        assert!(messages.iter().all(|x| x.contains("`shop::server`")));
+        assert!(messages.iter().all(|x| x.starts_with("name `Shop_SERVER`")));
+        assert_eq!(report.hints.len(), 2);
+    }
+
+    #[test]
+    fn redacts_sensitive_names() {
+        let report = report(
+            r#"
+variable secret of string {
+  sensitive = true
+  default   = "Secret_Name"
+}
+
+resource server of azure::mssql_server {
+  name = secret
+}
+"#,
+        );
+        assert!(!report.errors.is_empty());
+        for diagnostic in report.errors.iter().chain(&report.hints) {
+            assert!(!diagnostic.message.contains("Secret_Name"));
+            assert!(diagnostic.message.contains(REDACTED));
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Tracking of sensitive values through references.
+//!
+//! A value is sensitive when it comes from a `sensitive = true` variable or
+//! output, or from an attribute the provider schema marks as sensitive. Any
+//! reference to a sensitive value is sensitive too, whether it goes through
+//! another attribute, an output or a module input. The evaluator keeps
+//! track of it, as [`Value::Sensitive`](super::evaluate::Value::Sensitive).
+
+use super::evaluate::{Evaluator, Scope};
+use super::{Diagnostic, Location};
+use crate::parsing::syntax_tree::{
+    Expression, Literal, MapText, NestedBlock, ResourceClass, ResourceData, Statement, SyntaxTree,
+};
+use crate::project::instances::{Expansion, InstanceDeclaration};
+use crate::project::resolve::Resolution;
+use crate::project::{Module, Project};
+use crate::providers::schema::{SchemaKind, SchemaRegistry};
+use std::borrow::Cow;
+
+/// Text shown instead of a sensitive value.
+pub const REDACTED: &str = "(sensitive value)";
+
+/// Sensitivity of expressions, as the evaluator works it out for every
+/// instance of the module they are written in.
+pub struct Sensitivity<'a> {
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    schemas: &'a SchemaRegistry,
+    evaluator: Evaluator<'a>,
+}
+
+impl<'a> Sensitivity<'a> {
+    pub fn new(
+        project: &'a Project<'a>,
+        expansion: &'a Expansion<'a>,
+        schemas: &'a SchemaRegistry,
+    ) -> Sensitivity<'a> {
+        Sensitivity {
+            project,
+            expansion,
+            schemas,
+            evaluator: Evaluator::new(project).with_schemas(schemas),
+        }
+    }
+
+    /// Whether an expression of a declaration evaluates to a sensitive
+    /// value in the instance the declaration is deployed through.
+    pub fn is_sensitive(
+        &self,
+        declaration: &InstanceDeclaration<'a>,
+        expression: &Expression,
+    ) -> bool {
+        let scope = Scope::instance(self.project, self.expansion, declaration);
+        self.is_sensitive_in(&scope, expression)
+    }
+
+    /// Warnings for sensitive values reaching outputs or resource attributes
+    /// that are not sensitive themselves.
+    pub fn check(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for declaration in &self.expansion.declarations {
+            let data = declaration.data;
+            let file = match declaration.module.source_file(data) {
+                Some(file) => file,
+                None => continue,
+            };
+            let mut attributes = data.body.iter().flatten().collect::<Vec<_>>();
+            attributes.sort_by(|(a, _), (b, _)| a.value.cmp(&b.value));
+
+            for (name, expression) in attributes {
+                let exposed = match data.class {
+                    ResourceClass::Output => name.value == "value" && !is_marked_sensitive(data),
+                    ResourceClass::Custom | ResourceClass::Data => {
+                        schema_sensitivity(self.schemas, data, &name.value) == Some(false)
+                    }
+                    _ => false,
+                };
+                if !exposed || !self.is_sensitive(declaration, expression) {
+                    continue;
+                }
+                let message = match data.class {
+                    ResourceClass::Output => format!(
+                        "output `{}` exposes a sensitive value; mark it with `sensitive = true`",
+                        declaration.qualified_name()
+                    ),
+                    _ => format!(
+                        "attribute `{name}` of `{}` is not sensitive but receives a sensitive value",
+                        declaration.qualified_name()
+                    ),
+                };
+                diagnostics.push(Diagnostic {
+                    location: Location::new(file, data.span),
+                    message,
+                });
+            }
+        }
+        diagnostics
+    }
+
+    /// A copy of a syntax tree of `module` where the values of sensitive
+    /// attributes, in nested blocks too, are replaced by [`REDACTED`], fit
+    /// to be printed. A value is redacted when it is sensitive in any
+    /// instance of the module.
+    pub fn redacted(&self, module: &'a Module<'a>, tree: &SyntaxTree) -> SyntaxTree<'static> {
+        let scopes = self.scopes(module);
+        let is_sensitive =
+            |expression: &Expression| scopes.iter().any(|x| self.is_sensitive_in(x, expression));
+        let mut redacted = tree.map_text(&mut |text| Cow::Owned(text.to_string()));
+        for statement in &mut redacted.statements {
+            let data = match statement {
+                Statement::Resource(data) => data,
+                _ => continue,
+            };
+            let sensitive = data
+                .body
+                .iter()
+                .flatten()
+                .filter(|(name, expression)| {
+                    self.is_sensitive_attribute(module, data, &name.value)
+                        || is_sensitive(expression)
+                })
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            for name in sensitive {
+                if let Some(value) = data.body.as_mut().and_then(|body| body.get_mut(&name)) {
+                    *value = redacted_value();
+                }
+            }
+            for nested in &mut data.blocks {
+                redact_block(nested, &is_sensitive);
+            }
+        }
+        redacted
+    }
+
+    fn is_sensitive_in(&self, scope: &Scope<'a>, expression: &Expression) -> bool {
+        let value = self.evaluator.evaluate_in(scope, expression);
+        value.is_ok_and(|x| x.is_sensitive())
+    }
+
+    /// Every instance a module is deployed through, or the module on its
+    /// own when it has none.
+    fn scopes(&self, module: &'a Module<'a>) -> Vec<Scope<'a>> {
+        let mut instances = Vec::new();
+        let mut scopes = Vec::new();
+        for declaration in &self.expansion.declarations {
+            if !std::ptr::eq(declaration.module, module)
+                || instances.contains(&declaration.instance)
+            {
+                continue;
+            }
+            instances.push(declaration.instance.clone());
+            scopes.push(Scope::instance(self.project, self.expansion, declaration));
+        }
+        if scopes.is_empty() {
+            scopes.push(Scope::module(module));
+        }
+        scopes
+    }
+
+    fn is_sensitive_attribute(&self, module: &Module, data: &ResourceData, name: &str) -> bool {
+        match data.class {
+            ResourceClass::Variable => name == "default" && is_marked_sensitive(data),
+            ResourceClass::Output => name == "value" && is_marked_sensitive(data),
+            ResourceClass::Custom | ResourceClass::Data => {
+                schema_sensitivity(self.schemas, data, name) == Some(true)
+            }
+            ResourceClass::Module => match self.project.resolve(module, &data.type_name) {
+                Ok(Resolution::Module(target)) => target
+                    .inputs()
+                    .any(|input| input.name.value == name && is_marked_sensitive(input)),
+                _ => false,
+            },
+            ResourceClass::Provider => false,
+        }
+    }
+}
+
+fn redact_block(block: &mut NestedBlock, is_sensitive: &dyn Fn(&Expression) -> bool) {
+    for value in block.body.values_mut() {
+        if is_sensitive(value) {
+            *value = redacted_value();
+        }
+    }
+    for nested in &mut block.blocks {
+        redact_block(nested, is_sensitive);
+    }
+}
+
+/// Whether the provider schema marks an attribute of a resource or data
+/// source as sensitive, or `None` when the schema does not know it.
+pub fn schema_sensitivity(
+    schemas: &SchemaRegistry,
+    data: &ResourceData,
+    name: &str,
+) -> Option<bool> {
+    let kind = match data.class {
+        ResourceClass::Data => SchemaKind::DataSource,
+        _ => SchemaKind::Resource,
+    };
+    let schema = schemas.get(kind, &data.type_name.to_string())?;
+    schema
+        .attributes
+        .get(name)
+        .map(|attribute| attribute.sensitive)
+}
+
+/// Whether a variable or output is marked with `sensitive = true`.
+pub fn is_marked_sensitive(data: &ResourceData) -> bool {
+    matches!(
+        data.attribute("sensitive"),
+        Some(Expression::Literal(Literal::Boolean(true)))
+    )
+}
+
+fn redacted_value() -> Expression<'static> {
+    Expression::Literal(Literal::String(Cow::Borrowed(REDACTED)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    const SCHEMA: &str = r#"{
+  "provider_schemas": {
+    "registry.terraform.io/hashicorp/azurerm": {
+      "resource_schemas": {
+        "azurerm_storage_account": {
+          "block": {
+            "attributes": {
+              "name": { "type": "string", "required": true },
+              "primary_access_key": { "type": "string", "computed": true, "sensitive": true }
+            }
+          }
+        },
+        "azurerm_service_plan": {
+          "block": {
+            "attributes": {
+              "name": { "type": "string", "required": true }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+    const MODULE: &str = r#"
+variable password of string;
+
+output password {
+  value = password
+}
+"#;
+
+    fn with_sensitivity(source: &str, f: impl FnOnce(&Project, &Sensitivity)) {
+        let files = [("main.land", source), ("modules/db/main.land", MODULE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::from_json(SCHEMA);
+        let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+        f(&project, &sensitivity);
+    }
+
+    fn warnings(source: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+        with_sensitivity(source, |_, sensitivity| {
+            let diagnostics = sensitivity.check().into_iter();
+            warnings = diagnostics.map(|x| x.message).collect();
+        });
+        warnings
+    }
+
+    #[test]
+    fn follows_values_through_module_inputs() {
+        let source = r#"
+variable secret of string { sensitive = true }
+
+module shop of land::modules::db { password = secret }
+module blog of land::modules::db { password = "public" }
+"#;
+        assert_eq!(
+            warnings(source),
+            ["output `shop::password` exposes a sensitive value; mark it with `sensitive = true`"]
+        );
+    }
+
+    #[test]
+    fn takes_sensitive_attributes_from_the_schema() {
+        let source = r#"
+resource storage of azure::storage_account {
+  name = "storage"
+}
+
+resource plan of azure::service_plan {
+  name = storage.primary_access_key
+}
+
+output key {
+  value     = storage.primary_access_key
+  sensitive = true
+}
+"#;
+        assert_eq!(
+            warnings(source),
+            ["attribute `name` of `plan` is not sensitive but receives a sensitive value"]
+        );
+    }
+
+    #[test]
+    fn redacts_sensitive_values() {
+        let source = r#"
+variable secret of string {
+  sensitive = true
+  default   = "hunter2"
+}
+
+module shop of land::modules::db { password = secret }
+"#;
+        with_sensitivity(source, |project, sensitivity| {
+            for module in project.modules() {
+                for file in &module.files {
+                    let tree = sensitivity.redacted(module, &file.tree);
+                    let text = format!("{tree:?}");
+                    assert!(!text.contains("hunter2"), "{text}");
+                    assert!(text.contains(REDACTED), "{text}");
+                }
+            }
+        });
+    }
+}
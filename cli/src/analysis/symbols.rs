@@ -0,0 +1,65 @@
+use super::Location;
+use crate::parsing::syntax_tree::{ResourceClass, ResourceData, Statement};
+use crate::project::{Module, Project, SourceFile};
+
+/// A declaration of the project, with the module and file it is written in.
+#[derive(Debug, Clone, Copy)]
+pub struct Symbol<'a> {
+    pub module: &'a Module<'a>,
+    pub file: &'a SourceFile<'a>,
+    pub data: &'a ResourceData<'a>,
+}
+
+impl<'a> Symbol<'a> {
+    pub fn name(&self) -> &'a str {
+        &self.data.name.value
+    }
+
+    pub fn location(&self) -> Location {
+        Location::new(self.file, self.data.span)
+    }
+}
+
+/// Every declaration of a project, in module and source order.
+#[derive(Debug, Default)]
+pub struct SymbolTable<'a> {
+    pub symbols: Vec<Symbol<'a>>,
+}
+
+impl<'a> SymbolTable<'a> {
+    pub fn build(project: &'a Project<'a>) -> SymbolTable<'a> {
+        let mut symbols = Vec::new();
+        for module in project.modules() {
+            for file in &module.files {
+                for statement in &file.tree.statements {
+                    if let Statement::Resource(data) = statement {
+                        symbols.push(Symbol { module, file, data });
+                    }
+                }
+            }
+        }
+        SymbolTable { symbols }
+    }
+
+    pub fn in_module<'b>(&'b self, module: &'b Module) -> impl Iterator<Item = &'b Symbol<'a>> {
+        self.symbols
+            .iter()
+            .filter(move |symbol| symbol.module.path == module.path)
+    }
+
+    /// Namespaces opened by `provider` declarations, e.g. `azure` for
+    /// `provider main of azure;`. Resource types are written inside them.
+    pub fn provider_namespaces(&self) -> Vec<String> {
+        let mut namespaces = Vec::new();
+        for symbol in &self.symbols {
+            if symbol.data.class != ResourceClass::Provider {
+                continue;
+            }
+            let namespace = symbol.data.type_name.to_string();
+            if !namespaces.contains(&namespace) {
+                namespaces.push(namespace);
+            }
+        }
+        namespaces
+    }
+}
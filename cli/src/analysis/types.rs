@@ -0,0 +1,780 @@
+//! Type checking of the values assigned to attributes.
+//!
+//! Literals have the type of their value and references the type of what
+//! they point to: the declared type of a variable, or the schema type of a
+//! resource attribute. Anything else is `any`, which is compatible with
+//! every type.
+
+use super::symbols::{Symbol, SymbolTable};
+use super::{functions, suggest, with_suggestion, Diagnostic, Location};
+use crate::parsing::syntax_tree::{
+    Expression, FunctionCall, Literal, NameIdentifier, NestedBlock, ObjectPath, Reference,
+    ReferencePath, ResourceClass, ResourceData, Statement, TypeDefinition,
+};
+use crate::project::resolve::Resolution;
+use crate::project::{Module, Project};
+use crate::providers;
+use crate::providers::schema::{AttributeType as Type, Block, SchemaKind, SchemaRegistry};
+use std::collections::BTreeMap;
+
+const BUILTIN_TYPES: &[&str] = &["any", "bool", "number", "string"];
+
+/// Type aliases pointing at each other are cut off after this many steps.
+const MAX_TYPE_DEPTH: usize = 32;
+
+/// Attributes of a `variable` and the type each one expects, `None` standing
+/// for the type of the variable itself.
+const VARIABLE_ATTRIBUTES: &[(&str, Option<Type>)] = &[
+    ("default", None),
+    ("description", Some(Type::String)),
+    ("sensitive", Some(Type::Bool)),
+];
+
+const VALIDATION_ATTRIBUTES: &[(&str, Option<Type>)] = &[
+    ("condition", Some(Type::Bool)),
+    ("message", Some(Type::String)),
+];
+
+const OUTPUT_ATTRIBUTES: &[(&str, Option<Type>)] = &[
+    ("value", None),
+    ("description", Some(Type::String)),
+    ("sensitive", Some(Type::Bool)),
+];
+
+pub fn check(
+    project: &Project,
+    symbols: &SymbolTable,
+    schemas: &SchemaRegistry,
+) -> Vec<Diagnostic> {
+    let checker = Checker { project, schemas };
+    let mut diagnostics = Vec::new();
+
+    for module in project.modules() {
+        for file in &module.files {
+            for statement in &file.tree.statements {
+                if let Statement::Type(data) = statement {
+                    for message in checker.check_type_declaration(module, &data.definition) {
+                        diagnostics.push(Diagnostic {
+                            location: Location::new(file, data.span),
+                            message: format!("type `{}`: {message}", data.type_name),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    for symbol in &symbols.symbols {
+        for message in checker.check_declaration(symbol) {
+            diagnostics.push(Diagnostic {
+                location: symbol.location(),
+                message: format!("{} `{}`: {message}", symbol.data.class, symbol.data.name),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// The declared type of a variable, or the type of its default.
+pub fn variable_type(project: &Project, module: &Module, data: &ResourceData) -> Type {
+    let schemas = SchemaRegistry::new();
+    let checker = Checker {
+        project,
+        schemas: &schemas,
+    };
+    checker.variable_type(module, data)
+}
+
+struct Checker<'a> {
+    project: &'a Project<'a>,
+    schemas: &'a SchemaRegistry,
+}
+
+impl<'a> Checker<'a> {
+    fn check_type_declaration(&self, module: &Module, definition: &TypeDefinition) -> Vec<String> {
+        let paths = match definition {
+            TypeDefinition::Alias(path) => vec![path],
+            TypeDefinition::Object(fields) => fields.values().collect(),
+        };
+        paths
+            .into_iter()
+            .filter_map(|path| self.resolve_type(module, path, 0).err())
+            .collect()
+    }
+
+    fn check_declaration(&self, symbol: &Symbol) -> Vec<String> {
+        let data = symbol.data;
+        match data.class {
+            ResourceClass::Custom | ResourceClass::Data => self.check_resource(symbol),
+            ResourceClass::Variable => {
+                let mut errors = Vec::new();
+                let own_type = match data.type_name.sequence.is_empty() {
+                    true => Type::Dynamic,
+                    false => self
+                        .resolve_type(symbol.module, &data.type_name, 0)
+                        .unwrap_or_else(|error| {
+                            errors.push(error);
+                            Type::Dynamic
+                        }),
+                };
+                let attributes = sorted_attributes(data);
+                errors.extend(self.check_fixed_attributes(
+                    symbol.module,
+                    &attributes,
+                    VARIABLE_ATTRIBUTES,
+                    &own_type,
+                ));
+                errors.extend(self.check_validations(symbol));
+                errors
+            }
+            ResourceClass::Output => {
+                let attributes = sorted_attributes(data);
+                let mut errors = self.check_fixed_attributes(
+                    symbol.module,
+                    &attributes,
+                    OUTPUT_ATTRIBUTES,
+                    &Type::Dynamic,
+                );
+                if data.attribute("value").is_none() {
+                    errors.push(String::from("missing required attribute `value`"));
+                }
+                errors.extend(unknown_blocks(data));
+                errors
+            }
+            ResourceClass::Module => {
+                let mut errors = self.check_module_instance(symbol);
+                errors.extend(unknown_blocks(data));
+                errors
+            }
+            ResourceClass::Provider => self.check_values(symbol),
+        }
+    }
+
+    /// Checks the attributes of a resource against its provider schema, or
+    /// only the values it refers to when there is no schema.
+    fn check_resource(&self, symbol: &Symbol) -> Vec<String> {
+        let data = symbol.data;
+        let schema = match self.schema(data) {
+            Some(schema) => schema,
+            None => return self.check_values(symbol),
+        };
+        let described = data.type_name.to_string();
+        let attributes = sorted_attributes(data);
+        // Attributes linking a resource to its scope are set when it is
+        // built, from the `scoped` declaration it follows.
+        let statements = symbol.module.source_file(data).map(|x| &x.tree.statements);
+        let parents = statements.map(|x| providers::scope_parents(x, data));
+        let linked = parents
+            .into_iter()
+            .flatten()
+            .map(|(link, _)| link.attribute);
+        let linked = linked.collect::<Vec<_>>();
+        let blocks = &data.blocks;
+        self.check_body(
+            symbol.module,
+            &described,
+            &attributes,
+            blocks,
+            schema,
+            &linked,
+        )
+    }
+
+    /// Checks the attributes and nested blocks of a body against a schema
+    /// block, `described` naming the body in messages. `linked` attributes
+    /// are set even when the body does not write them.
+    fn check_body(
+        &self,
+        module: &Module,
+        described: &str,
+        attributes: &[(&NameIdentifier, &Expression)],
+        blocks: &[NestedBlock],
+        schema: &Block,
+        linked: &[&str],
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, expression) in attributes {
+            let expected = match schema.attributes.get(name.value.as_ref()) {
+                Some(attribute)
+                    if attribute.computed && !attribute.optional && !attribute.required =>
+                {
+                    errors.push(format!(
+                        "attribute `{name}` of `{described}` is computed by the provider and cannot be set"
+                    ));
+                    continue;
+                }
+                Some(attribute) => attribute.attribute_type.clone(),
+                None if schema.blocks.contains_key(name.value.as_ref()) => Type::Dynamic,
+                None => {
+                    let known = schema.attributes.keys().chain(schema.blocks.keys());
+                    let suggestion = suggest(&name.value, known.map(String::as_str));
+                    let message = format!("unknown attribute `{name}` for `{described}`");
+                    errors.push(with_suggestion(message, suggestion));
+                    continue;
+                }
+            };
+            errors.extend(self.check_value(module, name, expression, &expected));
+        }
+
+        for block in blocks {
+            let nested = match schema.blocks.get(block.name.value.as_ref()) {
+                Some(nested) => nested,
+                None => {
+                    let suggestion =
+                        suggest(&block.name.value, schema.blocks.keys().map(String::as_str));
+                    let message = format!("unknown block `{}` for `{described}`", block.name);
+                    errors.push(with_suggestion(message, suggestion));
+                    continue;
+                }
+            };
+            let described = format!("{described}.{}", block.name);
+            let attributes = sorted_block_attributes(block);
+            let blocks = &block.blocks;
+            let nested = &nested.block;
+            errors.extend(self.check_body(module, &described, &attributes, blocks, nested, &[]));
+        }
+
+        let is_set =
+            |name: &str| attributes.iter().any(|(x, _)| x.value == name) || linked.contains(&name);
+        for (name, attribute) in &schema.attributes {
+            if attribute.required && !is_set(name) {
+                errors.push(format!(
+                    "missing required attribute `{name}` of `{described}`"
+                ));
+            }
+        }
+        for (name, block) in &schema.blocks {
+            let count = blocks.iter().filter(|x| x.name.value == *name).count();
+            if block.min_items > count && !is_set(name) {
+                errors.push(format!("missing required block `{name}` of `{described}`"));
+            }
+        }
+        errors
+    }
+
+    fn check_fixed_attributes(
+        &self,
+        module: &Module,
+        attributes: &[(&NameIdentifier, &Expression)],
+        expected_types: &[(&str, Option<Type>)],
+        own_type: &Type,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, expression) in attributes {
+            let expected = match expected_types.iter().find(|(x, _)| name.value == *x) {
+                Some((_, expected)) => expected.as_ref().unwrap_or(own_type),
+                None => {
+                    let known = expected_types.iter().map(|(x, _)| *x);
+                    let suggestion = suggest(&name.value, known);
+                    let message = format!("unknown attribute `{name}`");
+                    errors.push(with_suggestion(message, suggestion));
+                    continue;
+                }
+            };
+            errors.extend(self.check_value(module, name, expression, expected));
+        }
+        errors
+    }
+
+    /// Checks the `validation` blocks of a variable, the only blocks a
+    /// variable can have.
+    fn check_validations(&self, symbol: &Symbol) -> Vec<String> {
+        let mut errors = Vec::new();
+        for block in &symbol.data.blocks {
+            if block.name.value != "validation" {
+                let suggestion = suggest(&block.name.value, ["validation"]);
+                let message = format!("unknown block `{}`", block.name);
+                errors.push(with_suggestion(message, suggestion));
+                continue;
+            }
+            let attributes = sorted_block_attributes(block);
+            let block_errors = self.check_fixed_attributes(
+                symbol.module,
+                &attributes,
+                VALIDATION_ATTRIBUTES,
+                &Type::Dynamic,
+            );
+            let mut block_errors = block_errors.into_iter().collect::<Vec<_>>();
+            for (name, _) in VALIDATION_ATTRIBUTES {
+                if block.attribute(name).is_none() {
+                    block_errors.push(format!("missing required attribute `{name}`"));
+                }
+            }
+            for nested in &block.blocks {
+                block_errors.push(format!("unknown block `{}`", nested.name));
+            }
+            errors.extend(
+                block_errors
+                    .into_iter()
+                    .map(|x| format!("`validation` block: {x}")),
+            );
+        }
+        errors
+    }
+
+    /// Checks the inputs given to a module instance against the types of
+    /// the variables of the instantiated module.
+    fn check_module_instance(&self, symbol: &Symbol) -> Vec<String> {
+        let data = symbol.data;
+        let target = match self.project.resolve(symbol.module, &data.type_name) {
+            Ok(Resolution::Module(target)) => target,
+            _ => return self.check_values(symbol),
+        };
+
+        let mut errors = Vec::new();
+        for (name, expression) in sorted_attributes(data) {
+            let expected = match target.inputs().find(|input| input.name == *name) {
+                Some(input) => self.variable_type(target, input),
+                None => Type::Dynamic,
+            };
+            errors.extend(self.check_value(symbol.module, name, expression, &expected));
+        }
+        errors
+    }
+
+    fn check_values(&self, symbol: &Symbol) -> Vec<String> {
+        let mut attributes = sorted_attributes(symbol.data);
+        let blocks = symbol.data.blocks.iter();
+        attributes.extend(blocks.flat_map(sorted_block_attributes));
+        attributes
+            .into_iter()
+            .filter_map(|(name, expression)| {
+                self.check_value(symbol.module, name, expression, &Type::Dynamic)
+            })
+            .collect()
+    }
+
+    fn check_value(
+        &self,
+        module: &Module,
+        name: &NameIdentifier,
+        expression: &Expression,
+        expected: &Type,
+    ) -> Option<String> {
+        match self.infer(module, expression) {
+            Ok(actual) if is_assignable(expected, &actual) => None,
+            Ok(actual) => Some(format!(
+                "attribute `{name}` expects {expected}, found {actual}"
+            )),
+            Err(error) => Some(format!("attribute `{name}`: {error}")),
+        }
+    }
+
+    /// The type of an expression as written in `module`.
+    fn infer(&self, module: &Module, expression: &Expression) -> Result<Type, String> {
+        let (path, members) = match expression {
+            Expression::Literal(Literal::String(_)) => return Ok(Type::String),
+            Expression::Literal(Literal::Number(_)) => return Ok(Type::Number),
+            Expression::Literal(Literal::Boolean(_)) => return Ok(Type::Bool),
+            Expression::List(items) => {
+                let items = items.iter().map(|x| self.infer(module, x));
+                return items.collect::<Result<_, _>>().map(Type::Tuple);
+            }
+            Expression::Call(call) => return self.infer_call(module, call),
+            Expression::Reference(path) => (path, &[][..]),
+            Expression::Object(ObjectPath {
+                object,
+                member_path,
+            }) => (object, member_path.as_deref().unwrap_or_default()),
+        };
+
+        let (module, data) = match self.project.resolve(module, path) {
+            Ok(Resolution::Declaration(module, data)) => (module, data),
+            _ => return Ok(Type::Dynamic),
+        };
+        let (mut current, members) = match (data.class, members.split_first()) {
+            (ResourceClass::Variable, _) => (self.variable_type(module, data), members),
+            (ResourceClass::Custom | ResourceClass::Data, Some((member, rest))) => {
+                let attribute_type = match self.schema(data) {
+                    Some(schema) => attribute_type(schema, data, member)?,
+                    None => Type::Dynamic,
+                };
+                (attribute_type, rest)
+            }
+            _ => return Ok(Type::Dynamic),
+        };
+
+        let mut described = path.to_string();
+        for member in members {
+            current = member_type(&current, &described, member)?;
+            described = format!("{described}.{member}");
+        }
+        Ok(current)
+    }
+
+    /// The result type of a function call, checking the arguments against
+    /// the parameters of the function.
+    fn infer_call(&self, module: &Module, call: &FunctionCall) -> Result<Type, String> {
+        let function = match functions::get(&call.function.value) {
+            Some(function) => function,
+            None => return Ok(Type::Dynamic),
+        };
+        function.check_arity(call.arguments.len())?;
+        for (index, argument) in call.arguments.iter().enumerate() {
+            let expected = function.parameter(index);
+            let actual = self.infer(module, argument)?;
+            if !is_assignable(expected, &actual) {
+                return Err(format!(
+                    "argument {} of `{}` expects {expected}, found {actual}",
+                    index + 1,
+                    function.name
+                ));
+            }
+        }
+        Ok(function.result.clone())
+    }
+
+    fn variable_type(&self, module: &Module, data: &ResourceData) -> Type {
+        if !data.type_name.sequence.is_empty() {
+            return self
+                .resolve_type(module, &data.type_name, 0)
+                .unwrap_or(Type::Dynamic);
+        }
+        match data.attribute("default") {
+            Some(Expression::Literal(literal)) => literal_type(literal),
+            _ => Type::Dynamic,
+        }
+    }
+
+    /// The type named by `path` in `module`: a builtin type, or a `type`
+    /// declaration of the module or of the module the path leads to.
+    fn resolve_type(
+        &self,
+        module: &Module,
+        path: &ReferencePath,
+        depth: usize,
+    ) -> Result<Type, String> {
+        if depth >= MAX_TYPE_DEPTH {
+            return Err(format!("type `{path}` refers back to itself"));
+        }
+
+        let (name, prefix) = match path.sequence.split_last() {
+            Some((Reference::Name(name), prefix)) => (name, prefix),
+            _ => return Err(format!("`{path}` is not a type")),
+        };
+        if prefix.is_empty() {
+            match name.value.as_ref() {
+                "any" => return Ok(Type::Dynamic),
+                "bool" => return Ok(Type::Bool),
+                "number" => return Ok(Type::Number),
+                "string" => return Ok(Type::String),
+                _ => {}
+            }
+        }
+
+        let scope = match prefix.is_empty() {
+            true => Some(module),
+            false => {
+                let prefix = ReferencePath {
+                    sequence: prefix.to_vec(),
+                };
+                match self.project.resolve(module, &prefix) {
+                    Ok(Resolution::Module(scope)) => Some(scope),
+                    _ => None,
+                }
+            }
+        };
+        let scope = scope.ok_or_else(|| format!("cannot find type `{path}`"))?;
+        let data = match scope.type_declaration(&name.value) {
+            Some(data) => data,
+            None => {
+                let declared = scope.statements().filter_map(|statement| match statement {
+                    Statement::Type(data) => Some(data.type_name.as_ref()),
+                    _ => None,
+                });
+                let builtins = BUILTIN_TYPES.iter().copied().filter(|_| prefix.is_empty());
+                let suggestion = suggest(&name.value, declared.chain(builtins));
+                let message = format!("cannot find type `{path}`");
+                return Err(with_suggestion(message, suggestion));
+            }
+        };
+
+        match &data.definition {
+            TypeDefinition::Alias(alias) => self.resolve_type(scope, alias, depth + 1),
+            TypeDefinition::Object(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(field, path)| {
+                        let field_type = self.resolve_type(scope, path, depth + 1);
+                        (field.to_string(), field_type.unwrap_or(Type::Dynamic))
+                    })
+                    .collect::<BTreeMap<_, _>>();
+                Ok(Type::Object(fields))
+            }
+        }
+    }
+
+    fn schema(&self, data: &ResourceData) -> Option<&'a Block> {
+        let kind = match data.class {
+            ResourceClass::Custom => SchemaKind::Resource,
+            ResourceClass::Data => SchemaKind::DataSource,
+            _ => return None,
+        };
+        self.schemas.get(kind, &data.type_name.to_string())
+    }
+}
+
+fn attribute_type(
+    schema: &Block,
+    data: &ResourceData,
+    member: &NameIdentifier,
+) -> Result<Type, String> {
+    if let Some(attribute) = schema.attributes.get(member.value.as_ref()) {
+        return Ok(attribute.attribute_type.clone());
+    }
+    if schema.blocks.contains_key(member.value.as_ref()) {
+        return Ok(Type::Dynamic);
+    }
+    let known = schema.attributes.keys().chain(schema.blocks.keys());
+    let suggestion = suggest(&member.value, known.map(String::as_str));
+    let message = format!(
+        "`{}` of type `{}` has no attribute `{member}`",
+        data.name, data.type_name
+    );
+    Err(with_suggestion(message, suggestion))
+}
+
+fn member_type(current: &Type, described: &str, member: &NameIdentifier) -> Result<Type, String> {
+    match current {
+        Type::Dynamic => Ok(Type::Dynamic),
+        Type::Map(inner) => Ok(inner.as_ref().clone()),
+        Type::Object(fields) => match fields.get(member.value.as_ref()) {
+            Some(field) => Ok(field.clone()),
+            None => {
+                let suggestion = suggest(&member.value, fields.keys().map(String::as_str));
+                let message = format!("`{described}` has no field `{member}`");
+                Err(with_suggestion(message, suggestion))
+            }
+        },
+        other => Err(format!(
+            "`{described}` is a {other} and has no field `{member}`"
+        )),
+    }
+}
+
+fn literal_type(literal: &Literal) -> Type {
+    match literal {
+        Literal::String(_) => Type::String,
+        Literal::Number(_) => Type::Number,
+        Literal::Boolean(_) => Type::Bool,
+    }
+}
+
+/// Whether a value of type `actual` can be used where `expected` is.
+pub fn is_assignable(expected: &Type, actual: &Type) -> bool {
+    match (expected, actual) {
+        (Type::Dynamic, _) | (_, Type::Dynamic) => true,
+        (Type::List(x) | Type::Set(x), Type::Tuple(items)) => {
+            items.iter().all(|item| is_assignable(x, item))
+        }
+        (Type::Map(x), Type::Object(fields)) => {
+            fields.values().all(|field| is_assignable(x, field))
+        }
+        (Type::List(x), Type::List(y))
+        | (Type::Set(x), Type::Set(y))
+        | (Type::Map(x), Type::Map(y)) => is_assignable(x, y),
+        (Type::Object(x), Type::Object(y)) => x
+            .iter()
+            .all(|(name, field)| y.get(name).is_some_and(|other| is_assignable(field, other))),
+        (Type::Tuple(x), Type::Tuple(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(a, b)| is_assignable(a, b))
+        }
+        (x, y) => x == y,
+    }
+}
+
+fn sorted_attributes<'d, 'src>(
+    data: &'d ResourceData<'src>,
+) -> Vec<(&'d NameIdentifier<'src>, &'d Expression<'src>)> {
+    let mut attributes = data.body.iter().flatten().collect::<Vec<_>>();
+    attributes.sort_by(|(a, _), (b, _)| a.value.cmp(&b.value));
+    attributes
+}
+
+fn sorted_block_attributes<'d, 'src>(
+    block: &'d NestedBlock<'src>,
+) -> Vec<(&'d NameIdentifier<'src>, &'d Expression<'src>)> {
+    let mut attributes = block.body.iter().collect::<Vec<_>>();
+    attributes.sort_by(|(a, _), (b, _)| a.value.cmp(&b.value));
+    attributes
+}
+
+fn unknown_blocks(data: &ResourceData) -> Vec<String> {
+    let blocks = data.blocks.iter();
+    blocks
+        .map(|block| format!("unknown block `{}`", block.name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    const SCHEMA: &str = r#"{
+  "provider_schemas": {
+    "registry.terraform.io/hashicorp/azurerm": {
+      "resource_schemas": {
+        "azurerm_resource_group": {
+          "block": {
+            "attributes": {
+              "name": { "type": "string", "required": true },
+              "location": { "type": "string", "required": true }
+            }
+          }
+        },
+        "azurerm_service_plan": {
+          "block": {
+            "attributes": {
+              "name": { "type": "string", "required": true },
+              "location": { "type": "string", "required": true },
+              "resource_group_name": { "type": "string", "required": true },
+              "sku_name": { "type": "string", "required": true },
+              "worker_count": { "type": "number", "optional": true },
+              "id": { "type": "string", "computed": true }
+            }
+          }
+        }
+      }
+    }
+  }
+}"#;
+
+    fn messages(source: &str) -> Vec<String> {
+        let sources = Sources::from_files(&[("main.land", source)]);
+        let project = Project::parse(&sources).unwrap();
+        let symbols = SymbolTable::build(&project);
+        let schemas = SchemaRegistry::from_json(SCHEMA);
+        let diagnostics = check(&project, &symbols, &schemas).into_iter();
+        diagnostics.map(|x| x.message).collect()
+    }
+
+    #[test]
+    fn scoped_parents_set_linked_attributes() {
+        let source = r#"
+resource rg of azure::resource_group scoped {
+  name = "rg"
+  location = "westeurope"
+}
+
+resource plan of azure::service_plan {
+  name = "plan"
+  sku = "B1"
+}
+"#;
+        assert_eq!(messages(source), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_missing_attributes_without_scope() {
+        let source = r#"
+resource plan of azure::service_plan {
+  name = "plan"
+  sku = "B1"
+}
+"#;
+        assert_eq!(
+            messages(source),
+            [
+                "resource `plan`: missing required attribute `location` of `azure::service_plan`",
+                "resource `plan`: missing required attribute `resource_group_name` of `azure::service_plan`",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_and_computed_attributes() {
+        let source = r#"
+resource plan of azure::service_plan {
+  name = "plan"
+  location = "westeurope"
+  resource_group_name = "rg"
+  sku = "B1"
+  workr_count = 2
+  id = "plan"
+}
+"#;
+        assert_eq!(
+            messages(source),
+            [
+                "resource `plan`: attribute `id` of `azure::service_plan` is computed by the provider and cannot be set",
+                "resource `plan`: unknown attribute `workr_count` for `azure::service_plan` (did you mean `worker_count`?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_mismatched_values() {
+        let source = r#"
+variable workers of string { default = "two" }
+
+resource plan of azure::service_plan {
+  name = "plan"
+  location = "westeurope"
+  resource_group_name = "rg"
+  sku = "B1"
+  worker_count = workers
+}
+"#;
+        assert_eq!(
+            messages(source),
+            ["resource `plan`: attribute `worker_count` expects number, found string"]
+        );
+    }
+
+    #[test]
+    fn checks_variables_and_outputs() {
+        let source = r#"
+variable sku of string {
+  default = 1
+  sensitiv = true
+  validation {
+    condition = "yes"
+  }
+}
+
+output plan {
+  description = "plan"
+}
+"#;
+        assert_eq!(
+            messages(source),
+            [
+                "variable `sku`: attribute `default` expects string, found number",
+                "variable `sku`: unknown attribute `sensitiv` (did you mean `sensitive`?)",
+                "variable `sku`: `validation` block: attribute `condition` expects bool, found string",
+                "variable `sku`: `validation` block: missing required attribute `message`",
+                "output `plan`: missing required attribute `value`",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_type_declarations() {
+        let source = r#"
+type name of string;
+
+type login {
+  user = name
+  password = strng
+}
+
+type loop of cycle;
+type cycle of loop;
+
+variable admin of login { default = "admin" }
+"#;
+        let mut messages = messages(source);
+        messages.sort();
+        assert_eq!(
+            messages,
+            [
+                "type `cycle`: type `loop` refers back to itself",
+                "type `login`: cannot find type `strng` (did you mean `string`?)",
+                "type `loop`: type `cycle` refers back to itself",
+                "variable `admin`: attribute `default` expects object({ password = any, user = string }), found string",
+            ]
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Custom checks of variable values, written as `validation` blocks:
+//!
+//! ```text
+//! variable sku of string {
+//!     validation {
+//!         condition = contains(["F1", "B1", "S1"], sku)
+//!         message = "sku must be one of F1, B1 or S1"
+//!     }
+//! }
+//! ```
+//!
+//! Conditions are evaluated once the values of the variables are known, for
+//! every instance of the module declaring the variable.
+
+use super::evaluate::{Evaluator, Scope, Value};
+use super::sensitivity::REDACTED;
+use super::{Diagnostic, Location};
+use crate::inputs::VariableValues;
+use crate::parsing::syntax_tree::ResourceClass;
+use crate::project::instances::Expansion;
+use crate::project::Project;
+
+/// Checks every validation of the deployed variables. Conditions that are
+/// not known before deployment, or that are not bools, are skipped; the type
+/// checks report the latter.
+pub fn check<'a>(
+    project: &'a Project<'a>,
+    expansion: &Expansion<'a>,
+    inputs: &'a VariableValues,
+) -> Vec<Diagnostic> {
+    let evaluator = Evaluator::new(project).with_inputs(inputs);
+    let mut errors = Vec::new();
+    for declaration in &expansion.declarations {
+        let data = declaration.data;
+        if data.class != ResourceClass::Variable || data.blocks("validation").next().is_none() {
+            continue;
+        }
+        let file = match declaration.module.source_file(data) {
+            Some(file) => file,
+            None => continue,
+        };
+        let scope = Scope::instance(project, expansion, declaration);
+        for validation in data.blocks("validation") {
+            let condition = match validation.attribute("condition") {
+                Some(condition) => evaluator.evaluate_in(&scope, condition),
+                None => continue,
+            };
+            let message = match condition {
+                Ok(value) if value.as_bool() == Some(false) => {
+                    let message = validation.attribute("message");
+                    match message.map(|x| evaluator.evaluate_in(&scope, x)) {
+                        Some(Ok(message)) if message.is_sensitive() => REDACTED.to_string(),
+                        Some(Ok(Value::String(message))) => message,
+                        _ => String::from("validation failed"),
+                    }
+                }
+                _ => continue,
+            };
+            errors.push(Diagnostic {
+                location: Location::new(file, data.span),
+                message: format!(
+                    "invalid value for variable `{}`: {message}",
+                    declaration.qualified_name()
+                ),
+            });
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inputs::Inputs;
+    use crate::project::Sources;
+
+    const MODULE: &str = r#"
+variable sku of string {
+  validation {
+    condition = contains(["F1", "B1", "S1"], sku)
+    message   = "sku must be one of F1, B1 or S1"
+  }
+}
+
+variable password of string {
+  sensitive = true
+  default   = "short"
+  validation {
+    condition = matches(password, "^.{12,}$")
+    message   = password
+  }
+}
+"#;
+
+    /// Messages of the failed validations, with the variables set by `flags`.
+    fn messages(source: &str, flags: &[&str]) -> Vec<String> {
+        let files = [("main.land", source), ("modules/plan/main.land", MODULE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let mut inputs = Inputs::new();
+        for flag in flags {
+            inputs.load_flag(flag).unwrap();
+        }
+        let inputs = inputs.resolve(&project).unwrap();
+        let expansion = project.expand();
+        let errors = check(&project, &expansion, &inputs).into_iter();
+        errors.map(|x| x.message).collect()
+    }
+
+    #[test]
+    fn checks_every_module_instance() {
+        let source = r#"
+module shop of land::modules::plan {
+  sku      = "P1"
+  password = "correct horse battery"
+}
+
+module blog of land::modules::plan {
+  sku      = "B1"
+  password = "correct horse battery"
+}
+"#;
+        assert_eq!(
+            messages(source, &[]),
+            ["invalid value for variable `shop::sku`: sku must be one of F1, B1 or S1"]
+        );
+    }
+
+    #[test]
+    fn checks_given_values_and_redacts_sensitive_messages() {
+        let source = r#"
+variable tier of string {
+  default = "Basic"
+  validation {
+    condition = not(equal(tier, "Free"))
+  }
+}
+
+module shop of land::modules::plan { sku = "S1" }
+"#;
+        assert_eq!(
+            messages(source, &[]),
+            [format!(
+                "invalid value for variable `shop::password`: {REDACTED}"
+            )]
+        );
+        assert_eq!(
+            messages(source, &["tier=Free"]),
+            [
+                String::from("invalid value for variable `tier`: validation failed"),
+                format!("invalid value for variable `shop::password`: {REDACTED}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_conditions_unknown_before_deployment() {
+        let source = "module shop of land::modules::plan {}\n";
+        assert_eq!(
+            messages(source, &[]),
+            [format!(
+                "invalid value for variable `shop::password`: {REDACTED}"
+            )]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::parsing::syntax_tree::{Annotation, Statement};
+
+/// Describes an annotation that landlord tooling knows how to consume.
+#[derive(Debug, Clone)]
+pub struct AnnotationSpec {
+    pub name: &'static str,
+    pub min_arguments: usize,
+    pub max_arguments: Option<usize>,
+    pub description: &'static str,
+}
+
+/// Known annotations, shared by linters, documentation and code generation
+/// so that all of them agree on what `@name(...)` means.
+#[derive(Debug, Clone, Default)]
+pub struct AnnotationRegistry {
+    specs: Vec<AnnotationSpec>,
+}
+
+impl AnnotationRegistry {
+    pub fn new() -> AnnotationRegistry {
+        AnnotationRegistry::default()
+    }
+
+    pub fn builtin() -> AnnotationRegistry {
+        let mut registry = AnnotationRegistry::new();
+        registry.register(AnnotationSpec {
+            name: "owner",
+            min_arguments: 1,
+            max_arguments: Some(1),
+            description: "Team or person owning the declaration",
+        });
+        registry.register(AnnotationSpec {
+            name: "deprecated",
+            min_arguments: 0,
+            max_arguments: Some(1),
+            description: "Marks the declaration as deprecated, optionally with a hint",
+        });
+        registry.register(AnnotationSpec {
+            name: "lint_allow",
+            min_arguments: 1,
+            max_arguments: None,
+            description: "Suppresses the given lint rules for the declaration",
+        });
+        registry.register(AnnotationSpec {
+            name: "depends_on",
+            min_arguments: 1,
+            max_arguments: None,
+            description: "Declarations to deploy before this one",
+        });
+        registry
+    }
+
+    pub fn register(&mut self, spec: AnnotationSpec) {
+        self.specs.retain(|x| x.name != spec.name);
+        self.specs.push(spec);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AnnotationSpec> {
+        self.specs.iter().find(|x| x.name == name)
+    }
+
+    pub fn specs(&self) -> &[AnnotationSpec] {
+        &self.specs
+    }
+
+    /// Warnings for unknown annotations or annotations with a wrong number
+    /// of arguments.
+    pub fn check(&self, statement: &Statement) -> Vec<String> {
+        statement
+            .annotations()
+            .iter()
+            .filter_map(|annotation| self.check_annotation(annotation))
+            .collect()
+    }
+
+    fn check_annotation(&self, annotation: &Annotation) -> Option<String> {
+        let name = &annotation.name;
+        let spec = match self.get(&name.value) {
+            Some(spec) => spec,
+            None => return Some(format!("unknown annotation `@{name}`")),
+        };
+
+        let count = annotation.arguments.len();
+        let too_few = count < spec.min_arguments;
+        let too_many = spec.max_arguments.is_some_and(|max| count > max);
+        if !too_few && !too_many {
+            return None;
+        }
+
+        let expected = match spec.max_arguments {
+            Some(max) if max == spec.min_arguments => format!("{max}"),
+            Some(max) => format!("{} to {max}", spec.min_arguments),
+            None => format!("at least {}", spec.min_arguments),
+        };
+        Some(format!(
+            "annotation `@{name}` takes {expected} argument(s) but {count} were given"
+        ))
+    }
+}
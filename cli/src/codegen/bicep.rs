@@ -0,0 +1,964 @@
+//! Bicep files deploying `azure` resources through Azure Resource Manager.
+//!
+//! `main.bicep` is deployed to a subscription and holds the variables, the
+//! outputs and the resource groups. Resources `scoped` into a resource group
+//! are written to a module named after it, e.g. `rg.bicep`, which
+//! `main.bicep` deploys to that resource group. Resource types and attributes
+//! are named by the `bicep` mappings, and validations of variables are left
+//! to `landlord validate`.
+
+use super::{
+    instance_input, label, lower_literal, resolve, scope_parents, split_type, Expr, Target,
+};
+use crate::analysis::functions;
+use crate::analysis::sensitivity::Sensitivity;
+use crate::analysis::types::variable_type;
+use crate::parsing::syntax_tree::{Expression, NestedBlock, ObjectPath, ResourceClass};
+use crate::project::instances::{Expansion, InstanceDeclaration};
+use crate::project::{Module, Project};
+use crate::providers::mappings::{Mappings, TypeMapping, BICEP};
+use crate::providers::schema::AttributeType;
+
+pub const EXTENSION: &str = "bicep";
+
+const MAIN: &str = "main";
+
+const INDENT: &str = "  ";
+
+/// Property path of attributes referring to the parent of a child
+/// resource, such as the server of a database.
+const PARENT: &str = "parent";
+
+/// Top level properties of a resource, in the order they are written.
+const PROPERTY_ORDER: &[&str] = &[
+    "parent",
+    "name",
+    "location",
+    "kind",
+    "sku",
+    "tags",
+    "properties",
+    "dependsOn",
+];
+
+/// The text of every Bicep file of the project, by file name, or the reasons
+/// the project cannot be deployed through Bicep.
+pub fn write<'a>(
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    sensitivity: &Sensitivity<'a>,
+    mappings: &Mappings,
+) -> Result<Vec<(String, String)>, Vec<String>> {
+    let mut lowering = Lowering {
+        project,
+        expansion,
+        sensitivity,
+        mappings,
+        files: vec![BicepFile::new(MAIN, None)],
+    };
+    lowering.lower()?;
+    Ok(lowering
+        .files
+        .iter()
+        .map(|file| lowering.text(file))
+        .collect())
+}
+
+/// A Bicep file, deployed to the subscription or to a resource group.
+struct BicepFile<'a> {
+    name: String,
+    resource_group: Option<&'a InstanceDeclaration<'a>>,
+    /// Variables of the deployment the file takes as parameters.
+    params: Vec<&'a InstanceDeclaration<'a>>,
+    /// Labels of the variables of module instances, and their definition
+    /// once lowered.
+    vars: Vec<(String, Option<String>)>,
+    resources: Vec<String>,
+    outputs: Vec<(String, String)>,
+}
+
+impl<'a> BicepFile<'a> {
+    fn new(name: &str, resource_group: Option<&'a InstanceDeclaration<'a>>) -> BicepFile<'a> {
+        BicepFile {
+            name: name.to_string(),
+            resource_group,
+            params: Vec::new(),
+            vars: Vec::new(),
+            resources: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    fn module_name(&self) -> String {
+        format!("{}_resources", self.name)
+    }
+}
+
+struct Lowering<'a, 's> {
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    sensitivity: &'s Sensitivity<'a>,
+    mappings: &'s Mappings,
+    files: Vec<BicepFile<'a>>,
+}
+
+impl<'a, 's> Lowering<'a, 's> {
+    fn lower(&mut self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        let declarations = self.expansion.declarations.iter();
+        for declaration in declarations.clone() {
+            let data = declaration.data;
+            let result = match data.class {
+                ResourceClass::Variable if declaration.instance.is_empty() => {
+                    self.files[0].params.push(declaration);
+                    Ok(())
+                }
+                ResourceClass::Custom | ResourceClass::Data => self.resource(declaration),
+                _ => Ok(()),
+            };
+            if let Err(error) = result {
+                let name = declaration.qualified_name();
+                errors.push(format!("{} `{name}`: {error}", data.class));
+            }
+        }
+        for declaration in declarations {
+            if declaration.data.class != ResourceClass::Output || !declaration.instance.is_empty() {
+                continue;
+            }
+            if let Err(error) = self.output(declaration) {
+                let name = declaration.qualified_name();
+                errors.push(format!("output `{name}`: {error}"));
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    /// Index of the file a resource is written to: the module of the
+    /// resource group it is deployed into, through its `scoped` parents.
+    fn home(&mut self, declaration: &'a InstanceDeclaration<'a>) -> Result<usize, String> {
+        if is_resource_group(declaration) {
+            return Ok(0);
+        }
+        // Child resources, such as databases, go with their parent.
+        let parents = scope_parents(self.expansion, declaration)?;
+        let resource_group = parents.iter().find(|(_, x)| is_resource_group(x));
+        if let Some((_, parent)) = resource_group {
+            let name = label(parent);
+            if let Some(index) = self.files.iter().position(|x| x.name == name) {
+                return Ok(index);
+            }
+            self.files.push(BicepFile::new(&name, Some(parent)));
+            return Ok(self.files.len() - 1);
+        }
+        if let Some((_, parent)) = parents.first() {
+            return self.home(parent);
+        }
+        Err(format!(
+            "`{}` is not deployed into a resource group",
+            declaration.data.name
+        ))
+    }
+
+    fn arm_type(&self, declaration: &InstanceDeclaration) -> Result<ArmType<'s>, String> {
+        let data = declaration.data;
+        let (namespace, name) = split_type(data)
+            .ok_or_else(|| format!("`{}` is not a provider type", data.type_name))?;
+        let mapping = self.mappings.type_mapping(&namespace, BICEP, &name);
+        match mapping {
+            Some(mapping) if mapping.name.is_some() && mapping.version.is_some() => Ok(ArmType {
+                namespace,
+                name,
+                mapping,
+                mappings: self.mappings,
+            }),
+            _ => Err(format!("`{}` has no Bicep type", data.type_name)),
+        }
+    }
+
+    fn resource(&mut self, declaration: &'a InstanceDeclaration<'a>) -> Result<(), String> {
+        let data = declaration.data;
+        let arm_type = self.arm_type(declaration)?;
+        let file = self.home(declaration)?;
+        let (module, instance) = (declaration.module, declaration.instance.as_slice());
+        let mut properties = Vec::new();
+
+        for (name, value) in &arm_type.mapping.fixed {
+            insert(&mut properties, name, Expr::String(value.to_string()));
+        }
+        let mut attributes = data.body.iter().flatten().collect::<Vec<_>>();
+        attributes.sort_by(|(a, _), (b, _)| a.value.cmp(&b.value));
+        for (name, expression) in attributes {
+            let path = arm_type.property(&name.value);
+            let is_name = path == "name";
+            if path.is_empty() || (data.class == ResourceClass::Data && !is_name) {
+                continue;
+            }
+            let mut value = self.expression(file, module, instance, expression)?;
+            if let Some(factor) = arm_type.mapping.factors.get(name.value.as_ref()) {
+                value = scaled(value, factor);
+            }
+            match path == PARENT {
+                true => insert(&mut properties, PARENT, parent(value)?),
+                false => insert(&mut properties, &path, value),
+            }
+        }
+        if data.class == ResourceClass::Custom {
+            for nested in &data.blocks {
+                self.nested_block(file, declaration, &arm_type, nested, &mut properties)?;
+            }
+        }
+
+        for (link, parent_node) in scope_parents(self.expansion, declaration)? {
+            if data.attribute(link.attribute).is_some() {
+                continue;
+            }
+            let path = arm_type.property(link.attribute);
+            match path.as_str() {
+                "" => {}
+                _ if is_resource_group(parent_node) => {
+                    let value = Expr::Traversal(vec![
+                        String::from("resourceGroup()"),
+                        link.value.to_string(),
+                    ]);
+                    insert(&mut properties, &path, value);
+                }
+                PARENT => {
+                    let value = self.declaration_reference(file, parent_node, Vec::new())?;
+                    insert(&mut properties, PARENT, parent(value)?);
+                }
+                _ => {
+                    let members = vec![link.value.to_string()];
+                    let value = self.declaration_reference(file, parent_node, members)?;
+                    insert(&mut properties, &path, value);
+                }
+            }
+        }
+        let has_location = properties.iter().any(|(x, _)| x == "location");
+        if is_resource_group(declaration) && !has_location && data.class == ResourceClass::Custom {
+            let location = vec![String::from("deployment()"), String::from("location")];
+            insert(&mut properties, "location", Expr::Traversal(location));
+        }
+
+        if let Some(annotation) = data.annotation("depends_on") {
+            let mut dependencies = Vec::new();
+            for argument in &annotation.arguments {
+                let value = self.expression(file, module, instance, argument)?;
+                dependencies.push(parent(value)?);
+            }
+            insert(&mut properties, "dependsOn", Expr::List(dependencies));
+        }
+        properties.sort_by_key(|(name, _)| {
+            let position = PROPERTY_ORDER.iter().position(|x| x == name);
+            position.unwrap_or(PROPERTY_ORDER.len())
+        });
+
+        let existing = match data.class {
+            ResourceClass::Data => " existing",
+            _ => "",
+        };
+        let text = format!(
+            "resource {} {}{existing} = {}\n",
+            label(declaration),
+            string(&arm_type.versioned_type()),
+            expression(&Expr::Object(properties), 0)
+        );
+        self.files[file].resources.push(text);
+        Ok(())
+    }
+
+    fn nested_block(
+        &mut self,
+        file: usize,
+        declaration: &'a InstanceDeclaration<'a>,
+        arm_type: &ArmType,
+        nested: &NestedBlock,
+        properties: &mut Vec<(String, Expr)>,
+    ) -> Result<(), String> {
+        let value = self.block_value(file, declaration, nested)?;
+        let path = arm_type.property(&nested.name.value);
+        // Blocks given more than once become an array.
+        let repeated = declaration.data.blocks(&nested.name.value).count() > 1;
+        match repeated {
+            true => match lookup(properties, &path) {
+                Some(Expr::List(items)) => items.push(value),
+                _ => insert(properties, &path, Expr::List(vec![value])),
+            },
+            false => insert(properties, &path, value),
+        }
+        Ok(())
+    }
+
+    fn block_value(
+        &mut self,
+        file: usize,
+        declaration: &'a InstanceDeclaration<'a>,
+        nested: &NestedBlock,
+    ) -> Result<Expr, String> {
+        let (module, instance) = (declaration.module, declaration.instance.as_slice());
+        let mut attributes = nested.body.iter().collect::<Vec<_>>();
+        attributes.sort_by(|(a, _), (b, _)| a.value.cmp(&b.value));
+        let mut fields = Vec::new();
+        for (name, expression) in attributes {
+            let value = self.expression(file, module, instance, expression)?;
+            fields.push((camel_case(&name.value), value));
+        }
+        for inner in &nested.blocks {
+            let value = self.block_value(file, declaration, inner)?;
+            fields.push((camel_case(&inner.name.value), value));
+        }
+        Ok(Expr::Object(fields))
+    }
+
+    fn output(&mut self, declaration: &'a InstanceDeclaration<'a>) -> Result<(), String> {
+        let (module, data) = (declaration.module, declaration.data);
+        let expression = data
+            .attribute("value")
+            .ok_or_else(|| String::from("missing attribute `value`"))?;
+        let value = self.expression(0, module, &declaration.instance, expression)?;
+        let is_sensitive = self.sensitivity.is_sensitive(declaration, expression);
+        let mut text = String::new();
+        if let Some(description) = data.description() {
+            text += &format!("@description({})\n", string(description));
+        }
+        if is_sensitive {
+            text += "@secure()\n";
+        }
+        text += &format!(
+            "output {} {} = {}\n",
+            label(declaration),
+            expression_type(&value),
+            self::expression(&value, 0)
+        );
+        self.files[0].outputs.push((label(declaration), text));
+        Ok(())
+    }
+
+    /// Lowers an expression written in `module`, deployed through `instance`,
+    /// for the given file.
+    fn expression(
+        &mut self,
+        file: usize,
+        module: &'a Module<'a>,
+        instance: &[String],
+        expression: &Expression,
+    ) -> Result<Expr, String> {
+        let (path, members) = match expression {
+            Expression::Literal(literal) => return Ok(lower_literal(literal)),
+            Expression::List(items) => {
+                let mut lowered = Vec::new();
+                for item in items {
+                    lowered.push(self.expression(file, module, instance, item)?);
+                }
+                return Ok(Expr::List(lowered));
+            }
+            Expression::Call(call) => {
+                let mut arguments = Vec::new();
+                for argument in &call.arguments {
+                    arguments.push(self.expression(file, module, instance, argument)?);
+                }
+                return lower_call(&call.function.value, arguments);
+            }
+            Expression::Reference(path) => (path, &[][..]),
+            Expression::Object(ObjectPath {
+                object,
+                member_path,
+            }) => (object, member_path.as_deref().unwrap_or_default()),
+        };
+        let members = members.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        match resolve(self.project, self.expansion, module, instance, path)? {
+            Target::Declaration(node) => self.declaration_reference(file, node, members),
+            Target::Instance(outputs) => match members.split_first() {
+                Some((member, rest)) => {
+                    let node = outputs.iter().find(|x| x.data.name.value == *member);
+                    let node = node.ok_or_else(|| format!("`{path}` has no output `{member}`"))?;
+                    self.declaration_reference(file, node, rest.to_vec())
+                }
+                None => {
+                    let mut fields = Vec::new();
+                    for node in outputs {
+                        let value = self.declaration_reference(file, node, Vec::new())?;
+                        fields.push((node.data.name.to_string(), value));
+                    }
+                    Ok(Expr::Object(fields))
+                }
+            },
+        }
+    }
+
+    /// A reference from a file to a deployed declaration.
+    fn declaration_reference(
+        &mut self,
+        file: usize,
+        node: &'a InstanceDeclaration<'a>,
+        members: Vec<String>,
+    ) -> Result<Expr, String> {
+        let data = node.data;
+        let label = label(node);
+        match data.class {
+            ResourceClass::Variable if node.instance.is_empty() => {
+                if !self.files[file]
+                    .params
+                    .iter()
+                    .any(|x| std::ptr::eq(*x, node))
+                {
+                    self.files[file].params.push(node);
+                }
+                Ok(Expr::Traversal([vec![label], members].concat()))
+            }
+            ResourceClass::Variable | ResourceClass::Output if !node.instance.is_empty() => {
+                self.var(file, node)?;
+                Ok(Expr::Traversal([vec![label], members].concat()))
+            }
+            ResourceClass::Custom | ResourceClass::Data => {
+                let arm_type = self.arm_type(node)?;
+                let mut path = Vec::new();
+                if let Some((first, rest)) = members.split_first() {
+                    let property = arm_type.property(first);
+                    if property.is_empty() || property == PARENT {
+                        return Err(format!("`{}.{first}` has no Bicep equivalent", data.name));
+                    }
+                    path.extend(property.split('.').map(String::from));
+                    path.extend(rest.iter().cloned());
+                }
+
+                let home = self.home(node)?;
+                if home == file {
+                    return Ok(Expr::Traversal([vec![label], path].concat()));
+                }
+                if self.files[file]
+                    .resource_group
+                    .is_some_and(|x| std::ptr::eq(x, node))
+                {
+                    let scope = vec![String::from("resourceGroup()")];
+                    return Ok(Expr::Traversal([scope, path].concat()));
+                }
+                if file != 0 || home == 0 {
+                    return Err(format!(
+                        "`{}` is deployed to another resource group",
+                        data.name
+                    ));
+                }
+                // The main file sees resources of resource groups through the
+                // outputs of their module.
+                let output = [vec![label.clone()], path.clone()].concat().join("_");
+                let home_file = &mut self.files[home];
+                if !home_file.outputs.iter().any(|(x, _)| *x == output) {
+                    let value = Expr::Traversal([vec![label], path].concat());
+                    let text = format!("output {output} string = {}\n", expression(&value, 0));
+                    home_file.outputs.push((output.clone(), text));
+                }
+                let module = home_file.module_name();
+                let outputs = vec![module, String::from("outputs"), output];
+                Ok(Expr::Traversal(outputs))
+            }
+            _ => Err(format!(
+                "{} `{}` cannot be referred to",
+                data.class, data.name
+            )),
+        }
+    }
+
+    /// Defines the variable of a module instance in a file, with the
+    /// variables it refers to.
+    fn var(&mut self, file: usize, node: &'a InstanceDeclaration<'a>) -> Result<(), String> {
+        let label = label(node);
+        if self.files[file].vars.iter().any(|(x, _)| *x == label) {
+            return Ok(());
+        }
+        self.files[file].vars.push((label.clone(), None));
+
+        let input = match node.data.class {
+            ResourceClass::Variable => instance_input(self.project, self.expansion, node),
+            _ => None,
+        };
+        let value = match input {
+            Some((module, instance, input)) => self.expression(file, module, instance, input)?,
+            None => {
+                let name = match node.data.class {
+                    ResourceClass::Variable => "default",
+                    _ => "value",
+                };
+                let expression = node
+                    .data
+                    .attribute(name)
+                    .ok_or_else(|| format!("`{}` has no value", node.qualified_name()))?;
+                self.expression(file, node.module, &node.instance, expression)?
+            }
+        };
+        let text = format!("var {label} = {}\n", expression(&value, 0));
+        let vars = &mut self.files[file].vars;
+        if let Some(var) = vars.iter_mut().find(|(x, _)| *x == label) {
+            var.1 = Some(text);
+        }
+        Ok(())
+    }
+
+    fn text(&self, file: &BicepFile<'a>) -> (String, String) {
+        let mut sections = Vec::new();
+        let scope = match file.resource_group {
+            Some(_) => "resourceGroup",
+            None => "subscription",
+        };
+        sections.push(format!("targetScope = '{scope}'\n"));
+
+        let mut params = file.params.clone();
+        params.sort_by_key(|node| self.position(node));
+        sections.extend(params.into_iter().map(|node| self.param(node)));
+        sections.extend(file.vars.iter().filter_map(|(_, text)| text.clone()));
+        sections.extend(file.resources.iter().cloned());
+
+        if file.resource_group.is_none() {
+            let modules = self.files.iter().filter(|x| x.resource_group.is_some());
+            sections.extend(modules.map(|module| self.module(module)));
+        }
+        sections.extend(file.outputs.iter().map(|(_, text)| text.clone()));
+        (format!("{}.{EXTENSION}", file.name), sections.join("\n"))
+    }
+
+    fn param(&self, node: &InstanceDeclaration) -> String {
+        let data = node.data;
+        let mut text = String::new();
+        if let Some(description) = data.description() {
+            text += &format!("@description({})\n", string(description));
+        }
+        let is_sensitive = data
+            .attribute("sensitive")
+            .is_some_and(|x| matches!(lower_literal_of(x), Some(Expr::Bool(true))));
+        if is_sensitive {
+            text += "@secure()\n";
+        }
+        let param_type = match variable_type(self.project, node.module, data) {
+            AttributeType::Dynamic => "object",
+            known => bicep_type(&known),
+        };
+        text += &format!("param {} {param_type}", label(node));
+        if let Some(default) = data.attribute("default").and_then(lower_literal_of) {
+            text += &format!(" = {}", expression(&default, 0));
+        }
+        text + "\n"
+    }
+
+    /// The module deploying the resources of a resource group.
+    fn module(&self, file: &BicepFile<'a>) -> String {
+        let mut params = file.params.clone();
+        params.sort_by_key(|node| self.position(node));
+        let params = params.into_iter().map(|node| {
+            let label = label(node);
+            (label.clone(), Expr::Traversal(vec![label]))
+        });
+        let mut fields = vec![
+            (
+                String::from("name"),
+                Expr::String(format!("{}-resources", file.name)),
+            ),
+            (
+                String::from("scope"),
+                Expr::Traversal(vec![file.name.clone()]),
+            ),
+        ];
+        let params = params.collect::<Vec<_>>();
+        if !params.is_empty() {
+            fields.push((String::from("params"), Expr::Object(params)));
+        }
+        format!(
+            "module {} {} = {}\n",
+            file.module_name(),
+            string(&format!("{}.{EXTENSION}", file.name)),
+            expression(&Expr::Object(fields), 0)
+        )
+    }
+
+    fn position(&self, node: &InstanceDeclaration) -> usize {
+        let declarations = self.expansion.declarations.iter();
+        let position = declarations
+            .into_iter()
+            .position(|x| std::ptr::eq(x.data, node.data) && x.instance == node.instance);
+        position.unwrap_or_default()
+    }
+}
+
+/// The Azure Resource Manager type of a landlord type.
+struct ArmType<'m> {
+    namespace: String,
+    name: String,
+    mapping: &'m TypeMapping,
+    mappings: &'m Mappings,
+}
+
+impl ArmType<'_> {
+    /// The type as written in Bicep, e.g. `Microsoft.Web/sites@2022-09-01`.
+    fn versioned_type(&self) -> String {
+        let resource_type = self.mapping.name.as_deref().unwrap_or_default();
+        let version = self.mapping.version.as_deref().unwrap_or_default();
+        format!("{resource_type}@{version}")
+    }
+
+    /// Dotted property path of an attribute, e.g. `sku.name` for `sku`.
+    /// Attributes without a mapping are taken to be resource properties of
+    /// the same name in camel case, and an empty path drops the attribute.
+    fn property(&self, attribute: &str) -> String {
+        let mapped = self
+            .mappings
+            .attribute(&self.namespace, BICEP, &self.name, attribute);
+        match mapped {
+            Some(path) => path.to_string(),
+            None => format!("properties.{}", camel_case(attribute)),
+        }
+    }
+}
+
+fn is_resource_group(declaration: &InstanceDeclaration) -> bool {
+    split_type(declaration.data)
+        .is_some_and(|(namespace, name)| namespace == "azure" && name == "resource_group")
+}
+
+/// The symbol of the resource a reference leads to, as the parent of a
+/// child resource.
+fn parent(value: Expr) -> Result<Expr, String> {
+    match value {
+        Expr::Traversal(path) if !path[0].ends_with(')') => {
+            Ok(Expr::Traversal(vec![path[0].clone()]))
+        }
+        _ => Err(String::from(
+            "parents must be resources of the same resource group",
+        )),
+    }
+}
+
+fn lower_literal_of(expression: &Expression) -> Option<Expr> {
+    match expression {
+        Expression::Literal(literal) => Some(lower_literal(literal)),
+        _ => None,
+    }
+}
+
+/// A value multiplied by a factor of its mapping, worked out when the value
+/// is a whole number literal.
+fn scaled(value: Expr, factor: &str) -> Expr {
+    let product = match &value {
+        Expr::Number(number) => number
+            .parse::<i64>()
+            .ok()
+            .zip(factor.parse::<i64>().ok())
+            .and_then(|(number, factor)| number.checked_mul(factor)),
+        _ => None,
+    };
+    match product {
+        Some(product) => Expr::Number(product.to_string()),
+        None => Expr::Operation("*", vec![value, Expr::Number(factor.to_string())]),
+    }
+}
+
+/// Sets a value at a dotted path of an object, creating the objects on the
+/// way.
+fn insert(fields: &mut Vec<(String, Expr)>, path: &str, value: Expr) {
+    let (first, rest) = match path.split_once('.') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (path, None),
+    };
+    let index = match fields.iter().position(|(x, _)| x == first) {
+        Some(index) => index,
+        None => {
+            fields.push((first.to_string(), Expr::Object(Vec::new())));
+            fields.len() - 1
+        }
+    };
+    match rest {
+        None => fields[index].1 = value,
+        Some(rest) => {
+            if !matches!(fields[index].1, Expr::Object(_)) {
+                fields[index].1 = Expr::Object(Vec::new());
+            }
+            if let Expr::Object(inner) = &mut fields[index].1 {
+                insert(inner, rest, value);
+            }
+        }
+    }
+}
+
+fn lookup<'e>(fields: &'e mut [(String, Expr)], path: &str) -> Option<&'e mut Expr> {
+    let (first, rest) = match path.split_once('.') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (path, None),
+    };
+    let (_, value) = fields.iter_mut().find(|(x, _)| x == first)?;
+    match (rest, value) {
+        (None, value) => Some(value),
+        (Some(rest), Expr::Object(inner)) => lookup(inner, rest),
+        _ => None,
+    }
+}
+
+/// The Bicep equivalent of a call of a builtin function.
+fn lower_call(function: &str, mut arguments: Vec<Expr>) -> Result<Expr, String> {
+    let builtin =
+        functions::get(function).ok_or_else(|| format!("unknown function `{function}`"))?;
+    builtin.check_arity(arguments.len())?;
+    let call = |name: &str, arguments| Ok(Expr::Call(name.to_string(), arguments));
+    match function {
+        "and" => Ok(Expr::Operation("&&", arguments)),
+        "or" => Ok(Expr::Operation("||", arguments)),
+        "equal" => Ok(Expr::Operation("==", arguments)),
+        "not" => Ok(Expr::Not(Box::new(arguments.remove(0)))),
+        "lower" => call("toLower", arguments),
+        "upper" => call("toUpper", arguments),
+        "starts_with" => call("startsWith", arguments),
+        "ends_with" => call("endsWith", arguments),
+        "matches" => Err(String::from("function `matches` has no Bicep equivalent")),
+        _ => call(function, arguments),
+    }
+}
+
+fn bicep_type(value: &AttributeType) -> &'static str {
+    match value {
+        AttributeType::String => "string",
+        AttributeType::Number => "int",
+        AttributeType::Bool => "bool",
+        AttributeType::List(_) | AttributeType::Set(_) | AttributeType::Tuple(_) => "array",
+        AttributeType::Map(_) | AttributeType::Object(_) | AttributeType::Dynamic => "object",
+    }
+}
+
+/// Type of an output, strings when it cannot be told from the value.
+fn expression_type(value: &Expr) -> &'static str {
+    match value {
+        Expr::Number(_) => "int",
+        Expr::Bool(_) | Expr::Operation(..) | Expr::Not(_) => "bool",
+        Expr::List(_) => "array",
+        Expr::Object(_) => "object",
+        _ => "string",
+    }
+}
+
+/// An expression, as written at the given indentation depth.
+fn expression(value: &Expr, depth: usize) -> String {
+    let indent = INDENT.repeat(depth + 1);
+    let closing = INDENT.repeat(depth);
+    match value {
+        Expr::String(value) => string(value),
+        Expr::Number(value) => value.clone(),
+        Expr::Bool(value) => value.to_string(),
+        Expr::List(items) if items.is_empty() => String::from("[]"),
+        Expr::List(items) => {
+            let items = items
+                .iter()
+                .map(|x| format!("{indent}{}\n", expression(x, depth + 1)));
+            format!("[\n{}{closing}]", items.collect::<String>())
+        }
+        Expr::Object(fields) if fields.is_empty() => String::from("{}"),
+        Expr::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(name, x)| format!("{indent}{}: {}\n", key(name), expression(x, depth + 1)));
+            format!("{{\n{}{closing}}}", fields.collect::<String>())
+        }
+        Expr::Traversal(path) => path.join("."),
+        Expr::Call(function, arguments) => {
+            let arguments = arguments.iter().map(|x| expression(x, depth));
+            format!("{function}({})", arguments.collect::<Vec<_>>().join(", "))
+        }
+        Expr::Operation(operator, operands) => {
+            let operands = operands.iter().map(|x| operand(x, depth));
+            operands.collect::<Vec<_>>().join(&format!(" {operator} "))
+        }
+        Expr::Not(value) => format!("!{}", operand(value, depth)),
+        Expr::Type(value) => value.to_string(),
+    }
+}
+
+fn operand(value: &Expr, depth: usize) -> String {
+    match value {
+        Expr::Operation(..) => format!("({})", expression(value, depth)),
+        _ => expression(value, depth),
+    }
+}
+
+/// An object key, quoted unless it is an identifier.
+fn key(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|x| x.is_ascii_alphabetic() || x == '_')
+        && chars.all(|x| x.is_ascii_alphanumeric() || x == '_');
+    match is_identifier {
+        true => name.to_string(),
+        false => string(name),
+    }
+}
+
+/// A single quoted string, escaping interpolation.
+fn string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => quoted.push_str("\\'"),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '$' if chars.peek() == Some(&'{') => quoted.push_str("\\$"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// `max_size_gb` becomes `maxSizeGb`.
+fn camel_case(name: &str) -> String {
+    let mut words = name.split('_');
+    let first = words.next().unwrap_or_default().to_string();
+    words.fold(first, |mut camel, word| {
+        let mut chars = word.chars();
+        camel.extend(chars.next().map(|x| x.to_ascii_uppercase()));
+        camel.push_str(chars.as_str());
+        camel
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+    use crate::providers::schema::SchemaRegistry;
+
+    const SOURCE: &str = r#"
+provider main of azure scoped;
+
+resource rg of azure::resource_group scoped {
+  name = "rg"
+}
+
+resource server of azure::mssql_server scoped {
+  name = "server"
+}
+
+resource db of azure::mssql_database {
+  name        = "db"
+  max_size_gb = 5
+}
+"#;
+
+    #[test]
+    fn converts_database_size_to_bytes() {
+        let sources = Sources::from_files(&[("main.land", SOURCE)]);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+        let mappings = Mappings::builtin();
+        let files = write(&project, &expansion, &sensitivity, &mappings).unwrap();
+
+        let text = files
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<String>();
+        assert!(text.contains("maxSizeBytes: 5368709120\n"), "{text}");
+        assert!(!text.contains("maxSizeGb"), "{text}");
+    }
+
+    #[test]
+    fn scales_references_in_the_deployment() {
+        let value = Expr::Traversal(vec![String::from("size")]);
+        let scaled = expression(&scaled(value, "1073741824"), 0);
+        assert_eq!(scaled, "size * 1073741824");
+    }
+
+    const INSTANCES: &str = r#"
+provider main of azure scoped;
+
+variable sku of string { default = "B1" }
+
+resource rg of azure::resource_group scoped {
+  name     = "rg"
+  location = "westeurope"
+}
+
+resource plan of azure::service_plan {
+  name = "plan"
+  sku  = sku
+}
+
+module shop of land::modules::db {
+  password = "hunter2"
+}
+
+output shop_password {
+  value     = shop.password
+  sensitive = true
+}
+"#;
+
+    const MODULE: &str = r#"
+variable password of string { sensitive = true }
+
+output password {
+  value     = password
+  sensitive = true
+}
+"#;
+
+    const MAIN: &str = r#"targetScope = 'subscription'
+
+param sku string = 'B1'
+
+var shop__output__password = shop__password
+
+var shop__password = 'hunter2'
+
+resource rg 'Microsoft.Resources/resourceGroups@2022-09-01' = {
+  name: 'rg'
+  location: 'westeurope'
+}
+
+module rg_resources 'rg.bicep' = {
+  name: 'rg-resources'
+  scope: rg
+  params: {
+    sku: sku
+  }
+}
+
+@secure()
+output shop_password string = shop__output__password
+"#;
+
+    const RESOURCE_GROUP: &str = r#"targetScope = 'resourceGroup'
+
+param sku string = 'B1'
+
+resource plan 'Microsoft.Web/serverfarms@2022-09-01' = {
+  name: 'plan'
+  location: resourceGroup().location
+  sku: {
+    name: sku
+  }
+}
+"#;
+
+    #[test]
+    fn writes_a_file_per_scope() {
+        let files = [("main.land", INSTANCES), ("modules/db/main.land", MODULE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+        let mappings = Mappings::builtin();
+        let files = write(&project, &expansion, &sensitivity, &mappings).unwrap();
+
+        let names = files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["main.bicep", "rg.bicep"]);
+        for ((name, text), expected) in files.iter().zip([MAIN, RESOURCE_GROUP]) {
+            assert_eq!(text, expected, "{name}");
+        }
+    }
+}
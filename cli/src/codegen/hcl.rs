@@ -0,0 +1,256 @@
+//! Terraform configuration written in HCL, the native syntax of Terraform,
+//! formatted the way `terraform fmt` would.
+
+use super::{Block, Body, Configuration, Expr};
+
+pub const EXTENSION: &str = "tf";
+
+const INDENT: &str = "  ";
+
+/// The text of every file of the configuration, by file name.
+pub fn write(configuration: &Configuration) -> Vec<(String, String)> {
+    let files = configuration.files.iter().map(|file| {
+        let blocks = file.blocks.iter().map(|x| block(x, 0));
+        let text = blocks.collect::<Vec<_>>().join("\n");
+        (format!("{}.{EXTENSION}", file.name), text)
+    });
+    files.collect()
+}
+
+fn block(block: &Block, depth: usize) -> String {
+    let mut header = vec![block.kind.clone()];
+    header.extend(block.labels.iter().map(|x| string(x)));
+    let header = header.join(" ");
+    if block.body.attributes.is_empty() && block.body.blocks.is_empty() {
+        return format!("{}{header} {{}}\n", INDENT.repeat(depth));
+    }
+    let indent = INDENT.repeat(depth);
+    format!(
+        "{indent}{header} {{\n{}{indent}}}\n",
+        body(&block.body, depth + 1)
+    )
+}
+
+/// Attributes, with their `=` aligned, followed by nested blocks.
+fn body(body: &Body, depth: usize) -> String {
+    let indent = INDENT.repeat(depth);
+    let width = body.attributes.iter().map(|(x, _)| x.len()).max();
+    let mut text = String::new();
+    for (name, value) in &body.attributes {
+        let name = format!("{name:<0$}", width.unwrap_or_default());
+        text += &format!("{indent}{name} = {}\n", expression(value, depth));
+    }
+    for nested in &body.blocks {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text += &block(nested, depth);
+    }
+    text
+}
+
+/// An expression, as written at the given indentation depth.
+pub fn expression(expression: &Expr, depth: usize) -> String {
+    match expression {
+        Expr::String(value) => string(value),
+        Expr::Number(value) => value.clone(),
+        Expr::Bool(value) => value.to_string(),
+        Expr::List(items) => {
+            let items = items.iter().map(|x| expression_inline(x, depth));
+            format!("[{}]", items.collect::<Vec<_>>().join(", "))
+        }
+        Expr::Object(fields) if fields.is_empty() => String::from("{}"),
+        Expr::Object(fields) => {
+            let body = Body {
+                attributes: fields.clone(),
+                blocks: Vec::new(),
+            };
+            let indent = INDENT.repeat(depth);
+            format!("{{\n{}{indent}}}", self::body(&body, depth + 1))
+        }
+        Expr::Traversal(path) => path.join("."),
+        Expr::Call(function, arguments) => {
+            let arguments = arguments.iter().map(|x| expression_inline(x, depth));
+            format!("{function}({})", arguments.collect::<Vec<_>>().join(", "))
+        }
+        Expr::Operation(operator, operands) => {
+            let operands = operands.iter().map(|x| operand(x, depth));
+            operands.collect::<Vec<_>>().join(&format!(" {operator} "))
+        }
+        Expr::Not(operand) => format!("!{}", self::operand(operand, depth)),
+        Expr::Type(value) => value.to_string(),
+    }
+}
+
+/// An expression written on a single line, as lists and arguments are.
+fn expression_inline(value: &Expr, depth: usize) -> String {
+    match value {
+        Expr::Object(fields) if !fields.is_empty() => {
+            let fields = fields
+                .iter()
+                .map(|(name, x)| format!("{name} = {}", expression_inline(x, depth)));
+            format!("{{ {} }}", fields.collect::<Vec<_>>().join(", "))
+        }
+        _ => expression(value, depth),
+    }
+}
+
+/// An operand of an operator, in parentheses when it is an operation too.
+fn operand(value: &Expr, depth: usize) -> String {
+    match value {
+        Expr::Operation(..) => format!("({})", expression(value, depth)),
+        _ => expression_inline(value, depth),
+    }
+}
+
+/// A quoted string, escaping the sequences Terraform would interpolate.
+pub fn string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                quoted.push(c);
+                quoted.push(c);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::sensitivity::Sensitivity;
+    use crate::codegen::lower;
+    use crate::project::{Project, Sources};
+    use crate::providers::mappings::Mappings;
+    use crate::providers::schema::SchemaRegistry;
+
+    const SOURCE: &str = r#"
+provider main of azure scoped;
+
+variable sku of string {
+  default = "B1"
+  validation {
+    condition = contains(["B1", "S1"], sku)
+    message   = "sku must be B1 or S1"
+  }
+}
+
+resource rg of azure::resource_group scoped {
+  name     = "rg"
+  location = "westeurope"
+}
+
+resource plan of azure::service_plan {
+  name = "plan"
+  sku  = sku
+}
+
+module shop of land::modules::db {
+  password = "hunter2"
+}
+
+output shop_password {
+  value     = shop.password
+  sensitive = true
+}
+"#;
+
+    const MODULE: &str = r#"
+variable password of string { sensitive = true }
+
+output password {
+  value     = password
+  sensitive = true
+}
+"#;
+
+    const PROVIDERS: &str = r#"terraform {
+  required_providers {
+    azurerm = {
+      source = "hashicorp/azurerm"
+    }
+  }
+}
+
+provider "azurerm" {
+  features {}
+}
+"#;
+
+    const VARIABLES: &str = r#"variable "sku" {
+  type    = string
+  default = "B1"
+
+  validation {
+    condition     = contains(["B1", "S1"], var.sku)
+    error_message = "sku must be B1 or S1"
+  }
+}
+"#;
+
+    const MAIN: &str = r#"locals {
+  shop__password         = "hunter2"
+  shop__output__password = local.shop__password
+}
+
+resource "azurerm_resource_group" "rg" {
+  location = "westeurope"
+  name     = "rg"
+}
+
+resource "azurerm_service_plan" "plan" {
+  location            = azurerm_resource_group.rg.location
+  name                = "plan"
+  resource_group_name = azurerm_resource_group.rg.name
+  sku_name            = var.sku
+}
+"#;
+
+    const OUTPUTS: &str = r#"output "shop_password" {
+  value     = local.shop__output__password
+  sensitive = true
+}
+"#;
+
+    #[test]
+    fn writes_terraform_files() {
+        let files = [("main.land", SOURCE), ("modules/db/main.land", MODULE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+        let mappings = Mappings::builtin();
+        let configuration = lower(&project, &expansion, &sensitivity, &mappings).unwrap();
+
+        let files = write(&configuration);
+        let names = files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["providers.tf", "variables.tf", "main.tf", "outputs.tf"]
+        );
+        for ((name, text), expected) in files.iter().zip([PROVIDERS, VARIABLES, MAIN, OUTPUTS]) {
+            assert_eq!(text, expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(string("say \"hi\"\n"), r#""say \"hi\"\n""#);
+        assert_eq!(string("${var.x} %{if}"), r#""$${var.x} %%{if}""#);
+    }
+}
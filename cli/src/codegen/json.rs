@@ -0,0 +1,198 @@
+//! Terraform configuration written in its JSON syntax, for pipelines that
+//! process the generated configuration further.
+//!
+//! Keys are sorted, so the same project always gives the same files. Values
+//! that are not plain data, such as references, are written as `${...}`
+//! templates.
+
+use super::{hcl, Block, Body, Configuration, Expr};
+use serde_json::{Map, Value};
+
+pub const EXTENSION: &str = "tf.json";
+
+/// The text of every file of the configuration, by file name.
+pub fn write(configuration: &Configuration) -> Vec<(String, String)> {
+    let files = configuration.files.iter().map(|file| {
+        let mut root = Map::new();
+        for block in &file.blocks {
+            insert(&mut root, block);
+        }
+        let text = serde_json::to_string_pretty(&Value::Object(root)).unwrap_or_default();
+        (format!("{}.{EXTENSION}", file.name), text + "\n")
+    });
+    files.collect()
+}
+
+/// Adds a block under its kind and labels. Blocks sharing them, such as
+/// aliased providers, are gathered in an array.
+fn insert(map: &mut Map<String, Value>, block: &Block) {
+    let (last, path) = match block.labels.split_last() {
+        Some((last, labels)) => (last, [&[block.kind.clone()][..], labels].concat()),
+        None => (&block.kind, Vec::new()),
+    };
+    let mut map = map;
+    for key in path {
+        let entry = map.entry(key).or_insert_with(|| Value::Object(Map::new()));
+        map = match entry {
+            Value::Object(inner) => inner,
+            _ => return,
+        };
+    }
+
+    let value = body(&block.body);
+    match map.get_mut(last) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+        None => {
+            map.insert(last.clone(), value);
+        }
+    }
+}
+
+fn body(body: &Body) -> Value {
+    let mut map = Map::new();
+    for (name, value) in &body.attributes {
+        let value = match name.as_str() {
+            // Dependencies name the declarations they refer to, without
+            // interpolation.
+            "depends_on" => dependencies(value),
+            _ => expression(value),
+        };
+        map.insert(name.clone(), value);
+    }
+    for nested in &body.blocks {
+        insert(&mut map, nested);
+    }
+    Value::Object(map)
+}
+
+fn dependencies(value: &Expr) -> Value {
+    match value {
+        Expr::List(items) => Value::Array(items.iter().map(dependencies).collect()),
+        other => Value::String(hcl::expression(other, 0)),
+    }
+}
+
+fn expression(value: &Expr) -> Value {
+    match value {
+        Expr::String(value) => Value::String(escape(value)),
+        Expr::Number(value) => match serde_json::from_str(value) {
+            Ok(number) => Value::Number(number),
+            Err(_) => Value::String(value.clone()),
+        },
+        Expr::Bool(value) => Value::Bool(*value),
+        Expr::List(items) => Value::Array(items.iter().map(expression).collect()),
+        Expr::Object(fields) => {
+            let fields = fields.iter().map(|(name, x)| (name.clone(), expression(x)));
+            Value::Object(fields.collect())
+        }
+        // Type constraints are written as strings of their HCL form.
+        Expr::Type(value) => Value::String(value.to_string()),
+        other => Value::String(format!("${{{}}}", hcl::expression(other, 0))),
+    }
+}
+
+/// Escapes the sequences Terraform would take as template directives in a
+/// JSON string.
+fn escape(value: &str) -> String {
+    value.replace("${", "$${").replace("%{", "%%{")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::sensitivity::Sensitivity;
+    use crate::codegen::lower;
+    use crate::project::{Project, Sources};
+    use crate::providers::mappings::Mappings;
+    use crate::providers::schema::SchemaRegistry;
+
+    const SOURCE: &str = r#"
+provider main of azure scoped;
+provider backup of azure {}
+
+resource rg of azure::resource_group scoped {
+  name     = "rg-${env}"
+  location = "westeurope"
+}
+
+@depends_on(rg)
+resource plan of azure::service_plan {
+  name         = "plan"
+  sku          = "B1"
+  worker_count = 2
+  zones        = ["1", "2"]
+}
+"#;
+
+    const PROVIDERS: &str = r#"{
+  "provider": {
+    "azurerm": [
+      {
+        "features": {}
+      },
+      {
+        "alias": "backup",
+        "features": {}
+      }
+    ]
+  },
+  "terraform": {
+    "required_providers": {
+      "azurerm": {
+        "source": "hashicorp/azurerm"
+      }
+    }
+  }
+}
+"#;
+
+    const MAIN: &str = r#"{
+  "resource": {
+    "azurerm_resource_group": {
+      "rg": {
+        "location": "westeurope",
+        "name": "rg-$${env}"
+      }
+    },
+    "azurerm_service_plan": {
+      "plan": {
+        "depends_on": [
+          "azurerm_resource_group.rg"
+        ],
+        "location": "${azurerm_resource_group.rg.location}",
+        "name": "plan",
+        "resource_group_name": "${azurerm_resource_group.rg.name}",
+        "sku_name": "B1",
+        "worker_count": 2,
+        "zones": [
+          "1",
+          "2"
+        ]
+      }
+    }
+  }
+}
+"#;
+
+    #[test]
+    fn writes_terraform_json_files() {
+        let sources = Sources::from_files(&[("main.land", SOURCE)]);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+        let mappings = Mappings::builtin();
+        let configuration = lower(&project, &expansion, &sensitivity, &mappings).unwrap();
+
+        let files = write(&configuration);
+        let names = files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["providers.tf.json", "main.tf.json"]);
+        for ((name, text), expected) in files.iter().zip([PROVIDERS, MAIN]) {
+            assert_eq!(text, expected, "{name}");
+        }
+    }
+}
@@ -0,0 +1,769 @@
+//! Lowering of a project into Terraform configuration, which the backends of
+//! `landlord build` write out.
+//!
+//! Module instances are flattened into a single configuration. Declarations
+//! are labelled with the path of the instance containing them, or else of
+//! their module, e.g. `web__plan` for `plan` in the instance `web`. Variables
+//! and outputs of instances become locals, outputs labelled like
+//! `web__output__url`, while those of modules deployed on their own become
+//! Terraform variables and outputs.
+
+pub mod bicep;
+pub mod hcl;
+pub mod json;
+
+use crate::analysis::functions;
+use crate::analysis::sensitivity::Sensitivity;
+use crate::analysis::types::variable_type;
+use crate::parsing::syntax_tree::{
+    Expression, Literal, NestedBlock, Number, ObjectPath, ReferencePath, ResourceClass,
+    ResourceData,
+};
+use crate::project::instances::{Expansion, InstanceDeclaration};
+use crate::project::resolve::Resolution;
+use crate::project::{Module, Project};
+use crate::providers::mappings::{Mappings, TERRAFORM};
+use crate::providers::{self, schema::AttributeType, split_type, ScopeLink};
+
+/// Terraform configuration, split into files the way Terraform projects
+/// usually are.
+#[derive(Debug, Default)]
+pub struct Configuration {
+    pub files: Vec<File>,
+}
+
+#[derive(Debug)]
+pub struct File {
+    /// Name of the file, without extension, e.g. `main`.
+    pub name: &'static str,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub kind: String,
+    pub labels: Vec<String>,
+    pub body: Body,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Body {
+    pub attributes: Vec<(String, Expr)>,
+    pub blocks: Vec<Block>,
+}
+
+/// An expression of the Terraform language.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    String(String),
+    /// A number as written in the source.
+    Number(String),
+    Bool(bool),
+    List(Vec<Expr>),
+    Object(Vec<(String, Expr)>),
+    /// A reference, such as `azurerm_resource_group.rg.name`.
+    Traversal(Vec<String>),
+    Call(String, Vec<Expr>),
+    /// Operands joined by a binary operator, such as `&&`.
+    Operation(&'static str, Vec<Expr>),
+    Not(Box<Expr>),
+    /// A type constraint, such as `list(string)`.
+    Type(AttributeType),
+}
+
+/// Lowers every deployed declaration of the project, or gives the reasons
+/// it cannot be.
+pub fn lower<'a>(
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    sensitivity: &Sensitivity,
+    mappings: &Mappings,
+) -> Result<Configuration, Vec<String>> {
+    let lowering = Lowering {
+        project,
+        expansion,
+        sensitivity,
+        mappings,
+    };
+    lowering.configuration()
+}
+
+struct Lowering<'a, 's> {
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    sensitivity: &'s Sensitivity<'a>,
+    mappings: &'s Mappings,
+}
+
+impl<'a> Lowering<'a, '_> {
+    fn configuration(&self) -> Result<Configuration, Vec<String>> {
+        let mut providers: Vec<Block> = Vec::new();
+        let mut variables = Vec::new();
+        let mut locals = Body::default();
+        let mut resources = Vec::new();
+        let mut outputs = Vec::new();
+        let mut errors = Vec::new();
+
+        for declaration in &self.expansion.declarations {
+            let data = declaration.data;
+            let label = label(declaration);
+            let result = match data.class {
+                ResourceClass::Provider => self
+                    .provider(declaration, &providers)
+                    .map(|block| providers.push(block)),
+                ResourceClass::Variable if declaration.instance.is_empty() => self
+                    .variable(declaration)
+                    .map(|block| variables.push(block)),
+                ResourceClass::Variable => self
+                    .instance_input(declaration)
+                    .map(|value| locals.attributes.push((label, value))),
+                ResourceClass::Output if declaration.instance.is_empty() => {
+                    self.output(declaration).map(|block| outputs.push(block))
+                }
+                ResourceClass::Output => self
+                    .attribute(declaration, "value")
+                    .map(|value| locals.attributes.push((label, value))),
+                ResourceClass::Custom | ResourceClass::Data => self
+                    .resource(declaration)
+                    .map(|block| resources.push(block)),
+                ResourceClass::Module => Ok(()),
+            };
+            if let Err(error) = result {
+                let name = declaration.qualified_name();
+                errors.push(format!("{} `{name}`: {error}", data.class));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut settings = vec![self.terraform_settings()];
+        settings.extend(providers);
+        let mut main = Vec::new();
+        if !locals.attributes.is_empty() {
+            main.push(block("locals", &[], locals));
+        }
+        main.extend(resources);
+
+        let files = [
+            ("providers", settings),
+            ("variables", variables),
+            ("main", main),
+            ("outputs", outputs),
+        ];
+        let files = files
+            .into_iter()
+            .filter(|(_, blocks)| !blocks.is_empty())
+            .map(|(name, blocks)| File { name, blocks })
+            .collect();
+        Ok(Configuration { files })
+    }
+
+    /// The `terraform` block requiring the provider of every declaration.
+    fn terraform_settings(&self) -> Block {
+        let mut names = Vec::new();
+        for declaration in &self.expansion.declarations {
+            let data = declaration.data;
+            let namespace = match data.class {
+                ResourceClass::Provider => data.type_name.to_string(),
+                ResourceClass::Custom | ResourceClass::Data => match split_type(data) {
+                    Some((namespace, _)) => namespace,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            let name = self.mappings.terraform_provider(&namespace).to_string();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names.sort();
+
+        let attributes = names.into_iter().map(|name| {
+            let source = Expr::String(providers::terraform_source(&name));
+            (name, Expr::Object(vec![(String::from("source"), source)]))
+        });
+        let required = Body {
+            attributes: attributes.collect(),
+            blocks: Vec::new(),
+        };
+        let body = Body {
+            attributes: Vec::new(),
+            blocks: vec![block("required_providers", &[], required)],
+        };
+        block("terraform", &[], body)
+    }
+
+    /// A provider configuration. Further configurations of the same provider
+    /// are told apart by an alias.
+    fn provider(
+        &self,
+        declaration: &InstanceDeclaration<'a>,
+        previous: &[Block],
+    ) -> Result<Block, String> {
+        let data = declaration.data;
+        let namespace = data.type_name.to_string();
+        let name = self.mappings.terraform_provider(&namespace);
+        let mut body = self.body(declaration)?;
+        if previous.iter().any(|x| x.labels[0] == name) {
+            let alias = Expr::String(label(declaration));
+            body.attributes.insert(0, (String::from("alias"), alias));
+        }
+        for required in providers::terraform_provider_blocks(name) {
+            if !body.blocks.iter().any(|x| x.kind == *required) {
+                body.blocks.push(block(required, &[], Body::default()));
+            }
+        }
+        Ok(block("provider", &[name], body))
+    }
+
+    fn variable(&self, declaration: &InstanceDeclaration<'a>) -> Result<Block, String> {
+        let (module, data) = (declaration.module, declaration.data);
+        let mut body = Body::default();
+        if !data.type_name.sequence.is_empty() {
+            let variable_type = variable_type(self.project, module, data);
+            body.attributes
+                .push((String::from("type"), Expr::Type(variable_type)));
+        }
+        if let Some(default) = data.attribute("default") {
+            let default = self.expression(module, &declaration.instance, default)?;
+            body.attributes.push((String::from("default"), default));
+        }
+        if let Some(description) = data.description() {
+            let description = Expr::String(description.to_string());
+            body.attributes
+                .push((String::from("description"), description));
+        }
+        if let Some(sensitive) = data.attribute("sensitive") {
+            let sensitive = self.expression(module, &declaration.instance, sensitive)?;
+            body.attributes.push((String::from("sensitive"), sensitive));
+        }
+
+        for validation in data.blocks("validation") {
+            let mut validation_body = Body::default();
+            let attributes = [("condition", "condition"), ("message", "error_message")];
+            for (name, terraform_name) in attributes {
+                if let Some(expression) = validation.attribute(name) {
+                    let value = self.expression(module, &declaration.instance, expression)?;
+                    validation_body
+                        .attributes
+                        .push((terraform_name.to_string(), value));
+                }
+            }
+            body.blocks.push(block("validation", &[], validation_body));
+        }
+        Ok(block("variable", &[&label(declaration)], body))
+    }
+
+    fn instance_input(&self, declaration: &InstanceDeclaration<'a>) -> Result<Expr, String> {
+        match instance_input(self.project, self.expansion, declaration) {
+            Some((module, instance, input)) => self.expression(module, instance, input),
+            None => self.attribute(declaration, "default"),
+        }
+    }
+
+    fn output(&self, declaration: &InstanceDeclaration<'a>) -> Result<Block, String> {
+        let (module, data) = (declaration.module, declaration.data);
+        let value = self.attribute(declaration, "value")?;
+        let mut body = Body::default();
+        body.attributes.push((String::from("value"), value));
+        if let Some(description) = data.description() {
+            let description = Expr::String(description.to_string());
+            body.attributes
+                .push((String::from("description"), description));
+        }
+        // Terraform refuses to output a sensitive value unless the output
+        // is marked as sensitive too.
+        let is_sensitive = data
+            .attribute("value")
+            .is_some_and(|value| self.sensitivity.is_sensitive(declaration, value));
+        match data.attribute("sensitive") {
+            Some(sensitive) if !is_sensitive => {
+                let sensitive = self.expression(module, &declaration.instance, sensitive)?;
+                body.attributes.push((String::from("sensitive"), sensitive));
+            }
+            _ if is_sensitive => body
+                .attributes
+                .push((String::from("sensitive"), Expr::Bool(true))),
+            _ => {}
+        }
+        Ok(block("output", &[&label(declaration)], body))
+    }
+
+    fn resource(&self, declaration: &InstanceDeclaration<'a>) -> Result<Block, String> {
+        let data = declaration.data;
+        let terraform_type = self
+            .terraform_type(data)
+            .ok_or_else(|| format!("`{}` is not a provider type", data.type_name))?;
+        let mut body = self.body(declaration)?;
+
+        for (attribute, value) in self.scope_attributes(declaration)? {
+            if !body.attributes.iter().any(|(x, _)| *x == attribute) {
+                body.attributes.push((attribute, value));
+            }
+        }
+        body.attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        if let Some(annotation) = data.annotation("depends_on") {
+            let dependencies = annotation.arguments.iter().map(|argument| {
+                self.expression(declaration.module, &declaration.instance, argument)
+            });
+            let dependencies = dependencies.collect::<Result<_, _>>()?;
+            body.attributes
+                .push((String::from("depends_on"), Expr::List(dependencies)));
+        }
+
+        let label = label(declaration);
+        match data.class {
+            ResourceClass::Data => Ok(block("data", &[&terraform_type, &label], body)),
+            _ => Ok(block("resource", &[&terraform_type, &label], body)),
+        }
+    }
+
+    /// Attributes linking a resource to the `scoped` declarations written
+    /// before it, so that its parents are explicit.
+    fn scope_attributes(
+        &self,
+        declaration: &InstanceDeclaration<'a>,
+    ) -> Result<Vec<(String, Expr)>, String> {
+        let mut attributes = Vec::new();
+        for (link, parent) in scope_parents(self.expansion, declaration)? {
+            let attribute = self.attribute_name(declaration.data, link.attribute);
+            let value = self.attribute_name(parent.data, link.value);
+            if let (Some(attribute), Some(value)) = (attribute, value) {
+                let mut path = self.declaration_path(parent)?;
+                path.push(value);
+                attributes.push((attribute, Expr::Traversal(path)));
+            }
+        }
+        Ok(attributes)
+    }
+
+    /// Attributes and nested blocks of a declaration, sorted by name.
+    fn body(&self, declaration: &InstanceDeclaration<'a>) -> Result<Body, String> {
+        let data = declaration.data;
+        let attributes = data.body.iter().flatten();
+        let mut body = Body::default();
+        for (name, expression) in attributes {
+            let name = match self.attribute_name(data, &name.value) {
+                Some(name) => name,
+                None => continue,
+            };
+            let value = self.expression(declaration.module, &declaration.instance, expression)?;
+            body.attributes.push((name, value));
+        }
+        body.attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for nested in &data.blocks {
+            let mut nested_block = self.nested_block(declaration, nested)?;
+            match self.attribute_name(data, &nested.name.value) {
+                Some(name) => nested_block.kind = name,
+                None => continue,
+            }
+            body.blocks.push(nested_block);
+        }
+        Ok(body)
+    }
+
+    fn nested_block(
+        &self,
+        declaration: &InstanceDeclaration<'a>,
+        nested: &NestedBlock,
+    ) -> Result<Block, String> {
+        let mut body = Body::default();
+        for (name, expression) in &nested.body {
+            let value = self.expression(declaration.module, &declaration.instance, expression)?;
+            body.attributes.push((name.to_string(), value));
+        }
+        body.attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for inner in &nested.blocks {
+            body.blocks.push(self.nested_block(declaration, inner)?);
+        }
+        Ok(block(&nested.name.value, &[], body))
+    }
+
+    fn attribute(&self, declaration: &InstanceDeclaration<'a>, name: &str) -> Result<Expr, String> {
+        let expression = declaration
+            .data
+            .attribute(name)
+            .ok_or_else(|| format!("missing attribute `{name}`"))?;
+        self.expression(declaration.module, &declaration.instance, expression)
+    }
+
+    /// Lowers an expression written in `module`, deployed through `instance`.
+    fn expression(
+        &self,
+        module: &'a Module<'a>,
+        instance: &[String],
+        expression: &Expression,
+    ) -> Result<Expr, String> {
+        let (path, members) = match expression {
+            Expression::Literal(literal) => return Ok(lower_literal(literal)),
+            Expression::List(items) => {
+                let items = items.iter().map(|x| self.expression(module, instance, x));
+                return items.collect::<Result<_, _>>().map(Expr::List);
+            }
+            Expression::Call(call) => {
+                let arguments = call.arguments.iter();
+                let arguments = arguments.map(|x| self.expression(module, instance, x));
+                let arguments = arguments.collect::<Result<Vec<_>, _>>()?;
+                return lower_call(&call.function.value, arguments);
+            }
+            Expression::Reference(path) => (path, &[][..]),
+            Expression::Object(ObjectPath {
+                object,
+                member_path,
+            }) => (object, member_path.as_deref().unwrap_or_default()),
+        };
+        let members = members.iter().map(|x| x.to_string());
+        self.reference(module, instance, path, members.collect())
+    }
+
+    fn reference(
+        &self,
+        module: &'a Module<'a>,
+        instance: &[String],
+        path: &ReferencePath,
+        members: Vec<String>,
+    ) -> Result<Expr, String> {
+        match resolve(self.project, self.expansion, module, instance, path)? {
+            Target::Declaration(node) => {
+                let traversal = self.declaration_path(node)?;
+                let mut members = members;
+                let is_resource =
+                    matches!(node.data.class, ResourceClass::Custom | ResourceClass::Data);
+                if let Some(member) = members.first_mut().filter(|_| is_resource) {
+                    *member = self.attribute_name(node.data, member).ok_or_else(|| {
+                        format!("`{}.{member}` has no Terraform equivalent", node.data.name)
+                    })?;
+                }
+                Ok(Expr::Traversal([traversal, members].concat()))
+            }
+            Target::Instance(outputs) => match members.split_first() {
+                Some((member, rest)) => {
+                    let node = outputs.iter().find(|x| x.data.name.value == *member);
+                    let node = node.ok_or_else(|| format!("`{path}` has no output `{member}`"))?;
+                    let traversal = self.declaration_path(node)?;
+                    Ok(Expr::Traversal([traversal, rest.to_vec()].concat()))
+                }
+                None => {
+                    let outputs = outputs.iter().map(|node| {
+                        let traversal = self.declaration_path(node)?;
+                        Ok((node.data.name.to_string(), Expr::Traversal(traversal)))
+                    });
+                    outputs.collect::<Result<_, String>>().map(Expr::Object)
+                }
+            },
+        }
+    }
+
+    /// The Terraform reference to a deployed declaration.
+    fn declaration_path(&self, node: &InstanceDeclaration) -> Result<Vec<String>, String> {
+        let data = node.data;
+        let label = label(node);
+        match data.class {
+            ResourceClass::Custom => Ok(vec![self.terraform_type(data).unwrap_or_default(), label]),
+            ResourceClass::Data => Ok(vec![
+                String::from("data"),
+                self.terraform_type(data).unwrap_or_default(),
+                label,
+            ]),
+            ResourceClass::Variable if node.instance.is_empty() => {
+                Ok(vec![String::from("var"), label])
+            }
+            ResourceClass::Variable => Ok(vec![String::from("local"), label]),
+            ResourceClass::Output if !node.instance.is_empty() => {
+                Ok(vec![String::from("local"), label])
+            }
+            _ => Err(format!(
+                "{} `{}` cannot be referred to",
+                data.class, data.name
+            )),
+        }
+    }
+
+    fn terraform_type(&self, data: &ResourceData) -> Option<String> {
+        let (namespace, name) = split_type(data)?;
+        Some(self.mappings.terraform_type(&namespace, &name))
+    }
+
+    /// Terraform name of an attribute of a declaration, or `None` when
+    /// Terraform has no such attribute.
+    fn attribute_name(&self, data: &ResourceData, attribute: &str) -> Option<String> {
+        let renamed = split_type(data).and_then(|(namespace, name)| {
+            let renamed = self
+                .mappings
+                .attribute(&namespace, TERRAFORM, &name, attribute);
+            renamed.map(String::from)
+        });
+        match renamed {
+            Some(renamed) if renamed.is_empty() => None,
+            Some(renamed) => Some(renamed),
+            None => Some(attribute.to_string()),
+        }
+    }
+}
+
+/// What a reference leads to once module instances are flattened.
+enum Target<'a> {
+    Declaration(&'a InstanceDeclaration<'a>),
+    /// A module instance, which is an object of its outputs.
+    Instance(Vec<&'a InstanceDeclaration<'a>>),
+}
+
+/// Resolves a path written in `module`, deployed through `instance`.
+fn resolve<'a>(
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    module: &'a Module<'a>,
+    instance: &[String],
+    path: &ReferencePath,
+) -> Result<Target<'a>, String> {
+    let data = match project.resolve(module, path) {
+        Ok(Resolution::Declaration(_, data)) => data,
+        Ok(Resolution::Module(_)) => return Err(format!("`{path}` is a module, not a value")),
+        Err(error) => return Err(error.to_string()),
+    };
+    if data.class != ResourceClass::Module {
+        let node = deployed(expansion, instance, data);
+        return node
+            .map(Target::Declaration)
+            .ok_or_else(|| format!("`{path}` is not deployed"));
+    }
+
+    let instance_path = [instance.to_vec(), vec![data.name.to_string()]].concat();
+    let target = match project.resolve(module, &data.type_name) {
+        Ok(Resolution::Module(target)) => target,
+        _ => return Err(format!("`{}` is not a module", data.type_name)),
+    };
+    let outputs = target
+        .outputs()
+        .filter_map(|output| deployed(expansion, &instance_path, output));
+    Ok(Target::Instance(outputs.collect()))
+}
+
+/// The deployed declaration as seen from `instance`: the one of the
+/// innermost enclosing instance, or the one outside of any instance.
+fn deployed<'a>(
+    expansion: &'a Expansion<'a>,
+    instance: &[String],
+    data: &ResourceData,
+) -> Option<&'a InstanceDeclaration<'a>> {
+    let declarations = &expansion.declarations;
+    (0..=instance.len()).rev().find_map(|length| {
+        let prefix = &instance[..length];
+        declarations
+            .iter()
+            .find(|x| x.instance == prefix && std::ptr::eq(x.data, data))
+    })
+}
+
+/// The input a module instance gives to one of its variables, with the
+/// module and instance it is written in, if any.
+fn instance_input<'a>(
+    project: &'a Project<'a>,
+    expansion: &'a Expansion<'a>,
+    declaration: &InstanceDeclaration<'a>,
+) -> Option<(&'a Module<'a>, &'a [String], &'a Expression<'a>)> {
+    let instances = expansion.instances.iter();
+    let instance = instances
+        .into_iter()
+        .find(|x| x.path == declaration.instance)?;
+    let input = instance
+        .declaration
+        .attribute(&declaration.data.name.value)?;
+    let module = project.declaring_module(instance.declaration)?;
+    let (_, outer) = instance.path.split_last()?;
+    Some((module, outer, input))
+}
+
+/// The `scoped` declarations written before a resource that it is linked
+/// to, nearest first, with the link to each of them.
+fn scope_parents<'a>(
+    expansion: &'a Expansion<'a>,
+    declaration: &InstanceDeclaration<'a>,
+) -> Result<Vec<(&'static ScopeLink, &'a InstanceDeclaration<'a>)>, String> {
+    let file = match declaration.module.source_file(declaration.data) {
+        Some(file) => file,
+        None => return Ok(Vec::new()),
+    };
+    let parents = providers::scope_parents(&file.tree.statements, declaration.data);
+    let parents = parents.into_iter().map(|(link, parent)| {
+        let node = deployed(expansion, &declaration.instance, parent);
+        let node = node.ok_or_else(|| format!("scope `{}` is not deployed", parent.name))?;
+        Ok((link, node))
+    });
+    parents.collect()
+}
+
+/// Label of a declaration in the flattened configuration.
+fn label(declaration: &InstanceDeclaration) -> String {
+    let prefix = match declaration.instance.is_empty() {
+        true => &declaration.module.path,
+        false => &declaration.instance,
+    };
+    // Outputs of instances are locals, as their variables are, and may have
+    // the same name as one of them.
+    let is_output_local =
+        declaration.data.class == ResourceClass::Output && !declaration.instance.is_empty();
+    let kind = is_output_local.then(|| String::from("output"));
+    let name = std::iter::once(declaration.data.name.to_string());
+    let parts = prefix.iter().cloned().chain(kind).chain(name);
+    parts.collect::<Vec<_>>().join("__")
+}
+
+fn block(kind: &str, labels: &[&str], body: Body) -> Block {
+    Block {
+        kind: kind.to_string(),
+        labels: labels.iter().map(|x| x.to_string()).collect(),
+        body,
+    }
+}
+
+fn lower_literal(literal: &Literal) -> Expr {
+    match literal {
+        Literal::Boolean(value) => Expr::Bool(*value),
+        Literal::String(value) => Expr::String(value.to_string()),
+        Literal::Number(Number::Integer(value) | Number::FloatingPoint(value)) => {
+            Expr::Number(value.to_string())
+        }
+    }
+}
+
+/// The Terraform equivalent of a call of a builtin function.
+fn lower_call(function: &str, mut arguments: Vec<Expr>) -> Result<Expr, String> {
+    let builtin =
+        functions::get(function).ok_or_else(|| format!("unknown function `{function}`"))?;
+    builtin.check_arity(arguments.len())?;
+    let call = |name: &str, arguments| Ok(Expr::Call(name.to_string(), arguments));
+    match function {
+        "and" => Ok(Expr::Operation("&&", arguments)),
+        "or" => Ok(Expr::Operation("||", arguments)),
+        "equal" => Ok(Expr::Operation("==", arguments)),
+        "not" => Ok(Expr::Not(Box::new(arguments.remove(0)))),
+        "matches" => {
+            arguments.swap(0, 1);
+            call("can", vec![Expr::Call(String::from("regex"), arguments)])
+        }
+        "starts_with" => call("startswith", arguments),
+        "ends_with" => call("endswith", arguments),
+        _ => call(function, arguments),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+    use crate::providers::schema::SchemaRegistry;
+    use std::path::Path;
+
+    /// Top-level arguments azurerm accepts for the types of the example
+    /// project.
+    const AZURERM_ARGUMENTS: &[(&str, &[&str])] = &[
+        (
+            "azurerm_resource_group",
+            &["location", "managed_by", "name", "tags"],
+        ),
+        (
+            "azurerm_service_plan",
+            &[
+                "app_service_environment_id",
+                "location",
+                "maximum_elastic_worker_count",
+                "name",
+                "os_type",
+                "per_site_scaling_enabled",
+                "resource_group_name",
+                "sku_name",
+                "tags",
+                "worker_count",
+                "zone_balancing_enabled",
+            ],
+        ),
+        (
+            "azurerm_mssql_server",
+            &[
+                "administrator_login",
+                "administrator_login_password",
+                "azuread_administrator",
+                "connection_policy",
+                "identity",
+                "location",
+                "minimum_tls_version",
+                "name",
+                "outbound_network_restriction_enabled",
+                "primary_user_assigned_identity_id",
+                "public_network_access_enabled",
+                "resource_group_name",
+                "tags",
+                "transparent_data_encryption_key_vault_key_id",
+                "version",
+            ],
+        ),
+        (
+            "azurerm_windows_web_app",
+            &[
+                "app_settings",
+                "client_affinity_enabled",
+                "enabled",
+                "https_only",
+                "identity",
+                "location",
+                "name",
+                "public_network_access_enabled",
+                "resource_group_name",
+                "service_plan_id",
+                "site_config",
+                "tags",
+                "virtual_network_subnet_id",
+            ],
+        ),
+        (
+            "azurerm_mssql_database",
+            &[
+                "auto_pause_delay_in_minutes",
+                "collation",
+                "create_mode",
+                "elastic_pool_id",
+                "license_type",
+                "max_size_gb",
+                "min_capacity",
+                "name",
+                "read_replica_count",
+                "read_scale",
+                "server_id",
+                "sku_name",
+                "storage_account_type",
+                "tags",
+                "zone_redundant",
+            ],
+        ),
+    ];
+
+    #[test]
+    fn example_uses_azurerm_arguments() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/projects/azure-webapps");
+        let sources = Sources::load(&root).unwrap();
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+        let mappings = Mappings::builtin();
+        let configuration = lower(&project, &expansion, &sensitivity, &mappings).unwrap();
+
+        let blocks = configuration.files.iter().flat_map(|x| &x.blocks);
+        let resources = blocks.filter(|x| x.kind == "resource").collect::<Vec<_>>();
+        assert_eq!(resources.len(), AZURERM_ARGUMENTS.len());
+        for resource in resources {
+            let resource_type = resource.labels[0].as_str();
+            let accepted = AZURERM_ARGUMENTS.iter().find(|(x, _)| *x == resource_type);
+            let (_, accepted) = accepted.unwrap_or_else(|| panic!("unexpected {resource_type}"));
+            for (name, _) in &resource.body.attributes {
+                assert!(
+                    accepted.contains(&name.as_str()),
+                    "{resource_type} has no argument `{name}`"
+                );
+            }
+        }
+    }
+}
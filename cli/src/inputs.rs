@@ -0,0 +1,459 @@
+//! Values of the variables of a deployment.
+//!
+//! Variables of modules deployed on their own, rather than through a
+//! `module` statement, are given values from these places, each one taking
+//! precedence over the ones before it:
+//!
+//! 1. the `default` of the variable,
+//! 2. `*.landvars` files in the project root, in alphabetical order,
+//! 3. `LANDLORD_VAR_<name>` environment variables,
+//! 4. `--var <name>=<value>` command line flags.
+//!
+//! A `.landvars` file assigns one variable per line, with values written as
+//! JSON:
+//!
+//! ```text
+//! # Shared settings
+//! region = "westeurope"
+//! tags = { "team": "payments" }
+//! ```
+//!
+//! Values from the environment and the command line are taken as they are
+//! for `string` and `any` variables and parsed as JSON otherwise. Variables
+//! of other modules than the root one are named with their module path, such
+//! as `network::cidr`, written `network__cidr` in environment variable names.
+//!
+//! Variables without a default may be left without a value until the project
+//! is deployed, and the values given for sensitive variables are never shown.
+
+use crate::analysis::evaluate::Value;
+use crate::analysis::sensitivity::is_marked_sensitive;
+use crate::analysis::types::{is_assignable, variable_type};
+use crate::parsing::syntax_tree::ResourceData;
+use crate::project::{Module, Project};
+use crate::providers::schema::AttributeType;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const VARS_EXTENSION: &str = "landvars";
+
+pub const ENV_PREFIX: &str = "LANDLORD_VAR_";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf, usize),
+    Environment(String),
+    CommandLine,
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path, line) => write!(f, "{}:{line}", path.display()),
+            Self::Environment(name) => write!(f, "environment variable {name}"),
+            Self::CommandLine => write!(f, "--var"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    pub source: Option<InputSource>,
+    pub message: String,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{source}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A value given for a variable, before it is checked against the project.
+#[derive(Debug, Clone)]
+struct RawInput {
+    text: String,
+    /// Whether the text is always JSON, or taken as is for strings.
+    is_json: bool,
+    source: InputSource,
+}
+
+/// Values given for variables, from every source, the last given value of
+/// each variable winning.
+#[derive(Debug, Default)]
+pub struct Inputs {
+    raw: BTreeMap<String, RawInput>,
+}
+
+impl Inputs {
+    pub fn new() -> Inputs {
+        Inputs::default()
+    }
+
+    /// Reads the `.landvars` files of the project root, the environment and
+    /// the `--var` flags of `args`.
+    pub fn load(root: &Path, args: &[String]) -> Result<Inputs, Vec<InputError>> {
+        let mut inputs = Inputs::new();
+        let mut errors = Vec::new();
+        for file in vars_files(root) {
+            if let Err(error) = inputs.load_file(&file) {
+                errors.extend(error);
+            }
+        }
+        inputs.load_environment(std::env::vars());
+        let flags = args.iter().zip(args.iter().skip(1));
+        for (_, assignment) in flags.filter(|(flag, _)| *flag == "--var") {
+            if let Err(error) = inputs.load_flag(assignment) {
+                errors.push(error);
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(inputs),
+            false => Err(errors),
+        }
+    }
+
+    pub fn load_file(&mut self, path: &Path) -> Result<(), Vec<InputError>> {
+        let text = std::fs::read_to_string(path).map_err(|error| {
+            vec![InputError {
+                source: Some(InputSource::File(path.to_path_buf(), 0)),
+                message: error.to_string(),
+            }]
+        })?;
+
+        let mut errors = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+            let source = InputSource::File(path.to_path_buf(), index + 1);
+            match line.split_once('=') {
+                Some((name, value)) if is_variable_name(name.trim()) => {
+                    self.insert(name.trim(), value.trim(), true, source);
+                }
+                _ => errors.push(InputError {
+                    source: Some(source),
+                    message: String::from("expected `<name> = <value>`"),
+                }),
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    pub fn load_environment(&mut self, vars: impl IntoIterator<Item = (String, String)>) {
+        let mut vars = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect::<Vec<_>>();
+        vars.sort();
+        for (key, value) in vars {
+            let name = key[ENV_PREFIX.len()..].replace("__", "::");
+            self.insert(&name, &value, false, InputSource::Environment(key));
+        }
+    }
+
+    /// Reads an assignment given as `<name>=<value>`.
+    pub fn load_flag(&mut self, assignment: &str) -> Result<(), InputError> {
+        match assignment.split_once('=') {
+            Some((name, value)) if is_variable_name(name) => {
+                self.insert(name, value, false, InputSource::CommandLine);
+                Ok(())
+            }
+            _ => Err(InputError {
+                source: Some(InputSource::CommandLine),
+                message: format!("expected `<name>=<value>`, found `{assignment}`"),
+            }),
+        }
+    }
+
+    fn insert(&mut self, name: &str, text: &str, is_json: bool, source: InputSource) {
+        let input = RawInput {
+            text: text.to_string(),
+            is_json,
+            source,
+        };
+        self.raw.insert(name.to_string(), input);
+    }
+
+    /// Names of the variables without a default that no input is given for.
+    /// They only need a value once the project is deployed.
+    pub fn missing(&self, project: &Project) -> Vec<String> {
+        let mut names = Vec::new();
+        for module in deployed_modules(project) {
+            for data in module.inputs() {
+                let name = qualified_name(module, data);
+                if data.attribute("default").is_none() && !self.raw.contains_key(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Checks the inputs against the variables of the project, giving the
+    /// value of every variable an input is given for.
+    ///
+    /// Inputs for unknown variables are errors, except for environment
+    /// variables, which may be meant for another project.
+    pub fn resolve(&self, project: &Project) -> Result<VariableValues, Vec<InputError>> {
+        let modules = deployed_modules(project);
+        let mut values = VariableValues::default();
+        let mut errors = Vec::new();
+
+        for (name, input) in &self.raw {
+            let variable = modules.iter().find_map(|module| {
+                let data = module
+                    .inputs()
+                    .find(|x| qualified_name(module, x) == *name)?;
+                Some((*module, data))
+            });
+            let (module, data) = match variable {
+                Some(variable) => variable,
+                None if matches!(input.source, InputSource::Environment(_)) => continue,
+                None => {
+                    errors.push(InputError {
+                        source: Some(input.source.clone()),
+                        message: format!("there is no variable `{name}`"),
+                    });
+                    continue;
+                }
+            };
+
+            let expected = variable_type(project, module, data);
+            let value = match parse_value(input, &expected) {
+                Ok(value) if is_assignable(&expected, &value.value_type()) => value,
+                Ok(value) => {
+                    let found = value.value_type();
+                    let message = match is_marked_sensitive(data) {
+                        true => format!("variable `{name}` expects a {expected}, found {found}"),
+                        false => format!(
+                            "variable `{name}` expects a {expected}, found {found} `{}`",
+                            input.text
+                        ),
+                    };
+                    errors.push(InputError {
+                        source: Some(input.source.clone()),
+                        message,
+                    });
+                    continue;
+                }
+                Err(error) => {
+                    errors.push(InputError {
+                        source: Some(input.source.clone()),
+                        message: format!("variable `{name}`: {error}"),
+                    });
+                    continue;
+                }
+            };
+            let key = (module.path.clone(), data.name.to_string());
+            values.values.insert(key, value);
+        }
+
+        match errors.is_empty() {
+            true => Ok(values),
+            false => Err(errors),
+        }
+    }
+}
+
+/// Values of variables, by module path and variable name.
+#[derive(Debug, Default)]
+pub struct VariableValues {
+    pub values: HashMap<(Vec<String>, String), Value>,
+}
+
+impl VariableValues {
+    pub fn get(&self, module: &Module, name: &str) -> Option<&Value> {
+        self.values.get(&(module.path.clone(), name.to_string()))
+    }
+}
+
+fn vars_files(root: &Path) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()),
+        Err(_) => return Vec::new(),
+    };
+    let mut files = entries
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|x| x.to_str()) == Some(VARS_EXTENSION))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn deployed_modules<'a>(project: &'a Project<'a>) -> Vec<&'a Module<'a>> {
+    let templates = project.instantiated_modules();
+    let modules = project.modules().into_iter();
+    modules
+        .filter(|module| !templates.iter().any(|x| x.path == module.path))
+        .collect()
+}
+
+fn qualified_name(module: &Module, data: &ResourceData) -> String {
+    let name = std::iter::once(data.name.value.as_ref());
+    let path = module.path.iter().map(String::as_str);
+    path.chain(name).collect::<Vec<_>>().join("::")
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut parts = name.split("::");
+    parts.all(|part| {
+        let mut chars = part.chars();
+        let first = chars.next();
+        first.is_some_and(|x| x.is_ascii_alphabetic() || x == '_')
+            && chars.all(|x| x.is_ascii_alphanumeric() || x == '_')
+    })
+}
+
+fn parse_value(input: &RawInput, expected: &AttributeType) -> Result<Value, String> {
+    let is_text = matches!(expected, AttributeType::String | AttributeType::Dynamic);
+    if !input.is_json && is_text {
+        return Ok(Value::String(input.text.clone()));
+    }
+    let json =
+        serde_json::from_str(&input.text).map_err(|error| format!("invalid value: {error}"))?;
+    from_json(&json)
+}
+
+fn from_json(json: &serde_json::Value) -> Result<Value, String> {
+    use serde_json::Value as Json;
+    match json {
+        Json::Null => Err(String::from("null is not a valid value")),
+        Json::Bool(value) => Ok(Value::Bool(*value)),
+        Json::Number(value) => Ok(Value::Number(value.as_f64().unwrap_or_default())),
+        Json::String(value) => Ok(Value::String(value.clone())),
+        Json::Array(items) => items
+            .iter()
+            .map(from_json)
+            .collect::<Result<_, _>>()
+            .map(Value::List),
+        Json::Object(fields) => fields
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), from_json(value)?)))
+            .collect::<Result<_, String>>()
+            .map(Value::Object),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    const SOURCE: &str = r#"
+variable sku of string { default = "S1" }
+variable workers of number;
+variable password of number { sensitive = true }
+"#;
+
+    const NETWORK: &str = "variable cidr of string;\n";
+
+    fn with_project(f: impl FnOnce(&Project)) {
+        let files = [("main.land", SOURCE), ("network/main.land", NETWORK)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        f(&project);
+    }
+
+    fn environment(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        let vars = vars.iter();
+        vars.map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn errors(inputs: &Inputs) -> Vec<String> {
+        let mut messages = Vec::new();
+        with_project(|project| {
+            let errors = inputs.resolve(project).unwrap_err().into_iter();
+            messages = errors.map(|x| x.to_string()).collect();
+        });
+        messages
+    }
+
+    #[test]
+    fn later_sources_take_precedence() {
+        let path = std::env::temp_dir().join(format!("landlord-{}.landvars", std::process::id()));
+        let text =
+            "# Shared settings\nsku = \"B1\"\nworkers = 2\nnetwork::cidr = \"10.0.0.0/16\"\n";
+        std::fs::write(&path, text).unwrap();
+        let mut inputs = Inputs::new();
+        inputs.load_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        inputs.load_environment(environment(&[
+            ("LANDLORD_VAR_workers", "3"),
+            ("LANDLORD_VAR_network__cidr", "10.1.0.0/16"),
+            ("LANDLORD_VAR_unknown", "ignored"),
+            ("PATH", "/usr/bin"),
+        ]));
+        inputs.load_flag("workers=4").unwrap();
+
+        with_project(|project| {
+            let values = inputs.resolve(project).unwrap();
+            let root = project.module(&[]).unwrap();
+            let network = project.module(&[String::from("network")]).unwrap();
+            assert_eq!(
+                values.get(root, "sku"),
+                Some(&Value::String(String::from("B1")))
+            );
+            assert_eq!(values.get(root, "workers"), Some(&Value::Number(4.0)));
+            assert_eq!(
+                values.get(network, "cidr"),
+                Some(&Value::String(String::from("10.1.0.0/16")))
+            );
+            assert_eq!(inputs.missing(project), ["password"]);
+        });
+    }
+
+    #[test]
+    fn reports_malformed_assignments() {
+        let path =
+            std::env::temp_dir().join(format!("landlord-{}-bad.landvars", std::process::id()));
+        std::fs::write(&path, "sku = \"B1\"\n\nnot an assignment\n").unwrap();
+        let errors = Inputs::new().load_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        let messages = errors
+            .iter()
+            .map(|x| x.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages, ["expected `<name> = <value>`"]);
+        assert_eq!(errors[0].source, Some(InputSource::File(path, 3)));
+
+        let error = Inputs::new().load_flag("1sku=B1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "--var: expected `<name>=<value>`, found `1sku=B1`"
+        );
+    }
+
+    #[test]
+    fn checks_inputs_against_the_variables() {
+        let mut inputs = Inputs::new();
+        inputs.load_flag("size=2").unwrap();
+        inputs.load_flag("workers=two").unwrap();
+        assert_eq!(
+            errors(&inputs),
+            [
+                "--var: there is no variable `size`",
+                "--var: variable `workers`: invalid value: expected ident at line 1 column 2",
+            ]
+        );
+
+        let mut inputs = Inputs::new();
+        inputs.load_flag("workers=\"two\"").unwrap();
+        inputs.load_flag("password=\"hunter2\"").unwrap();
+        assert_eq!(
+            errors(&inputs),
+            [
+                "--var: variable `password` expects a number, found string",
+                "--var: variable `workers` expects a number, found string `\"two\"`",
+            ]
+        );
+    }
+}
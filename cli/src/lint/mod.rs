@@ -0,0 +1,361 @@
+//! Lints: checks for code that is valid but likely wrong or hard to
+//! maintain.
+//!
+//! Every rule has a name and a default severity that the project can change
+//! in `.landlord/lint.json`:
+//!
+//! ```json
+//! { "rules": { "unused_variable": "error", "unreferenced_resource": "warning" } }
+//! ```
+//!
+//! A single declaration opts out of rules with `@lint_allow(rule, ...)`.
+//!
+//! Rules that only make sense in production look at the environment, set
+//! with `"environment": "production"` in the same file.
+
+pub mod rules;
+pub mod security;
+
+use crate::analysis::symbols::SymbolTable;
+use crate::analysis::{Diagnostic, Location};
+use crate::inputs::VariableValues;
+use crate::parsing::syntax_tree::{Expression, ObjectPath, ResourceData};
+use crate::project::instances::Expansion;
+use crate::project::resolve::Resolution;
+use crate::project::{Module, Project};
+use crate::providers::schema::SchemaRegistry;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Path of the lint configuration, relative to the project root.
+pub const CONFIG_FILE: &str = ".landlord/lint.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Allow,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn parse(text: &str) -> Option<Severity> {
+        match text {
+            "allow" => Some(Self::Allow),
+            "warning" => Some(Self::Warning),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Allow => "allow",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        };
+        f.pad(name)
+    }
+}
+
+/// What a lint rule can look at: the parsed project, its semantic model and
+/// the values given to its variables.
+pub struct LintContext<'a> {
+    pub project: &'a Project<'a>,
+    pub symbols: &'a SymbolTable<'a>,
+    pub expansion: &'a Expansion<'a>,
+    pub inputs: &'a VariableValues,
+    pub schemas: &'a SchemaRegistry,
+    pub environment: Option<String>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn is_production(&self) -> bool {
+        matches!(self.environment.as_deref(), Some("production" | "prod"))
+    }
+
+    /// Declarations referred to by any attribute or `@depends_on` of the
+    /// project.
+    pub fn referenced(&self) -> Vec<&'a ResourceData<'a>> {
+        let mut referenced = Vec::new();
+        for symbol in &self.symbols.symbols {
+            let data = symbol.data;
+            let attributes = data.body.iter().flat_map(|body| body.values());
+            let nested = data.nested_attributes().into_iter().map(|(_, x)| x);
+            let dependencies = data.annotation("depends_on").map(|x| x.arguments.iter());
+            let expressions = attributes
+                .chain(nested)
+                .chain(dependencies.into_iter().flatten());
+            for expression in expressions.flat_map(Expression::walk) {
+                let target = self.target(symbol.module, expression);
+                if let Some(target) = target.filter(|x| !std::ptr::eq(*x, data)) {
+                    referenced.push(target);
+                }
+            }
+        }
+        referenced
+    }
+
+    fn target(
+        &self,
+        module: &'a Module<'a>,
+        expression: &Expression,
+    ) -> Option<&'a ResourceData<'a>> {
+        let path = match expression {
+            Expression::Literal(_) | Expression::List(_) | Expression::Call(_) => return None,
+            Expression::Reference(path) => path,
+            Expression::Object(ObjectPath { object, .. }) => object,
+        };
+        match self.project.resolve(module, path) {
+            Ok(Resolution::Declaration(_, data)) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+/// A finding of a lint rule about a declaration.
+#[derive(Debug)]
+pub struct Finding<'a> {
+    pub data: &'a ResourceData<'a>,
+    pub location: Location,
+    pub message: String,
+}
+
+pub trait Lint {
+    /// Name used in configuration and in `@lint_allow(...)`.
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn default_severity(&self) -> Severity;
+
+    /// How to fix findings of the rule.
+    fn remediation(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn check<'a>(&self, context: &LintContext<'a>) -> Vec<Finding<'a>>;
+}
+
+#[derive(Debug)]
+pub struct LintDiagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub diagnostic: Diagnostic,
+    pub remediation: Option<&'static str>,
+}
+
+impl fmt::Display for LintDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.rule, self.diagnostic)?;
+        match self.remediation {
+            Some(remediation) => write!(f, "\n  = help: {remediation}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Linter {
+    rules: Vec<Box<dyn Lint>>,
+    severities: HashMap<String, Severity>,
+    pub environment: Option<String>,
+}
+
+impl Linter {
+    pub fn new() -> Linter {
+        Linter::default()
+    }
+
+    pub fn builtin() -> Linter {
+        let mut linter = Linter::new();
+        linter.register(Box::new(rules::UnusedVariable));
+        linter.register(Box::new(rules::UnreferencedResource));
+        linter.register(Box::new(rules::MissingScope));
+        linter.register(Box::new(rules::NamingConvention));
+        for rule in security::RULES {
+            linter.register(Box::new(*rule));
+        }
+        linter
+    }
+
+    pub fn register(&mut self, rule: Box<dyn Lint>) {
+        self.rules.retain(|x| x.name() != rule.name());
+        self.rules.push(rule);
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn Lint> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    pub fn severity(&self, rule: &dyn Lint) -> Severity {
+        self.severities
+            .get(rule.name())
+            .copied()
+            .unwrap_or_else(|| rule.default_severity())
+    }
+
+    pub fn set_severity(&mut self, rule: &str, severity: Severity) -> Result<(), String> {
+        if !self.rules.iter().any(|x| x.name() == rule) {
+            return Err(format!("unknown lint rule `{rule}`"));
+        }
+        self.severities.insert(rule.to_string(), severity);
+        Ok(())
+    }
+
+    /// Applies the severities of the project lint configuration, if any.
+    pub fn configure(&mut self, root: &Path) -> Result<(), String> {
+        let path = root.join(CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(());
+        }
+        let error = |message: String| format!("{}: {message}", path.display());
+        let text = std::fs::read_to_string(&path).map_err(|x| error(x.to_string()))?;
+        let config: Value = serde_json::from_str(&text).map_err(|x| error(x.to_string()))?;
+        if let Some(environment) = config.get("environment").and_then(Value::as_str) {
+            self.environment = Some(environment.to_string());
+        }
+        let rules = config.get("rules").and_then(Value::as_object);
+        for (rule, severity) in rules.into_iter().flatten() {
+            let severity = severity
+                .as_str()
+                .and_then(Severity::parse)
+                .ok_or_else(|| error(format!("invalid severity for `{rule}`: {severity}")))?;
+            self.set_severity(rule, severity).map_err(error)?;
+        }
+        Ok(())
+    }
+
+    /// Runs every rule that is not allowed, dropping findings suppressed by
+    /// `@lint_allow(...)`.
+    pub fn run(&self, context: &LintContext) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for rule in self.rules() {
+            let severity = self.severity(rule);
+            if severity == Severity::Allow {
+                continue;
+            }
+            for finding in rule.check(context) {
+                if is_suppressed(finding.data, rule.name()) {
+                    continue;
+                }
+                diagnostics.push(LintDiagnostic {
+                    rule: rule.name(),
+                    severity,
+                    diagnostic: Diagnostic {
+                        location: finding.location,
+                        message: finding.message,
+                    },
+                    remediation: rule.remediation(),
+                });
+            }
+        }
+        diagnostics
+    }
+}
+
+pub fn is_suppressed(data: &ResourceData, rule: &str) -> bool {
+    data.annotations
+        .iter()
+        .filter(|annotation| annotation.name.value == "lint_allow")
+        .any(|annotation| annotation.name_arguments().iter().any(|x| x == rule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    /// Rule and message of the findings of the builtin rules.
+    fn lint(linter: &Linter, source: &str) -> Vec<String> {
+        let sources = Sources::from_files(&[("main.land", source)]);
+        let project = Project::parse(&sources).unwrap();
+        let symbols = SymbolTable::build(&project);
+        let expansion = project.expand();
+        let inputs = VariableValues::default();
+        let schemas = SchemaRegistry::new();
+        let context = LintContext {
+            project: &project,
+            symbols: &symbols,
+            expansion: &expansion,
+            inputs: &inputs,
+            schemas: &schemas,
+            environment: linter.environment.clone(),
+        };
+        let diagnostics = linter.run(&context).into_iter();
+        diagnostics
+            .map(|x| format!("{}[{}]: {}", x.severity, x.rule, x.diagnostic.message))
+            .collect()
+    }
+
+    #[test]
+    fn applies_configured_severities() {
+        let source = "variable unused of string;\n";
+        let mut linter = Linter::builtin();
+        assert_eq!(
+            lint(&linter, source),
+            ["warning[unused_variable]: variable `unused` is never used"]
+        );
+
+        linter
+            .set_severity("unused_variable", Severity::Error)
+            .unwrap();
+        assert_eq!(
+            lint(&linter, source),
+            ["error[unused_variable]: variable `unused` is never used"]
+        );
+
+        linter
+            .set_severity("unused_variable", Severity::Allow)
+            .unwrap();
+        assert!(lint(&linter, source).is_empty());
+
+        let error = linter.set_severity("unused_variables", Severity::Error);
+        assert_eq!(
+            error,
+            Err(String::from("unknown lint rule `unused_variables`"))
+        );
+    }
+
+    #[test]
+    fn skips_suppressed_declarations() {
+        let source = r#"
+@lint_allow(unused_variable)
+variable unused of string;
+
+@lint_allow(missing_scope)
+variable Other of string;
+"#;
+        assert_eq!(
+            lint(&Linter::builtin(), source),
+            [
+                "warning[unused_variable]: variable `Other` is never used",
+                "warning[naming_convention]: variable `Other` should have a snake_case name: `other`",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_the_project_configuration() {
+        let root = std::env::temp_dir().join(format!("landlord-lint-{}", std::process::id()));
+        std::fs::create_dir_all(root.join(".landlord")).unwrap();
+        let config = root.join(CONFIG_FILE);
+
+        let mut linter = Linter::builtin();
+        let text = r#"{ "environment": "production", "rules": { "unused_variable": "error" } }"#;
+        std::fs::write(&config, text).unwrap();
+        linter.configure(&root).unwrap();
+        assert_eq!(linter.environment.as_deref(), Some("production"));
+        let rule = linter.rules().find(|x| x.name() == "unused_variable");
+        assert_eq!(linter.severity(rule.unwrap()), Severity::Error);
+
+        std::fs::write(&config, r#"{ "rules": { "unused_variable": "fatal" } }"#).unwrap();
+        let error = linter.configure(&root).unwrap_err();
+        assert!(error.ends_with("invalid severity for `unused_variable`: \"fatal\""));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
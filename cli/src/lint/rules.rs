@@ -0,0 +1,281 @@
+use super::{Finding, Lint, LintContext, Severity};
+use crate::analysis::symbols::Symbol;
+use crate::parsing::syntax_tree::{ResourceClass, Statement};
+
+pub struct UnusedVariable;
+
+impl Lint for UnusedVariable {
+    fn name(&self) -> &'static str {
+        "unused_variable"
+    }
+
+    fn description(&self) -> &'static str {
+        "Variables that no attribute refers to"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check<'a>(&self, context: &LintContext<'a>) -> Vec<Finding<'a>> {
+        let referenced = context.referenced();
+        let unused = context.symbols.symbols.iter().filter(|symbol| {
+            symbol.data.class == ResourceClass::Variable
+                && !referenced.iter().any(|x| std::ptr::eq(*x, symbol.data))
+        });
+        unused
+            .map(|symbol| {
+                finding(
+                    symbol,
+                    format!("variable `{}` is never used", symbol.name()),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Most resources are leaves nobody refers to, so this rule is only useful
+/// to hunt for forgotten declarations and is allowed by default.
+pub struct UnreferencedResource;
+
+impl Lint for UnreferencedResource {
+    fn name(&self) -> &'static str {
+        "unreferenced_resource"
+    }
+
+    fn description(&self) -> &'static str {
+        "Resources and data sources that no attribute refers to"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Allow
+    }
+
+    fn check<'a>(&self, context: &LintContext<'a>) -> Vec<Finding<'a>> {
+        let referenced = context.referenced();
+        let unreferenced = context.symbols.symbols.iter().filter(|symbol| {
+            matches!(
+                symbol.data.class,
+                ResourceClass::Custom | ResourceClass::Data
+            ) && !referenced.iter().any(|x| std::ptr::eq(*x, symbol.data))
+        });
+        unreferenced
+            .map(|symbol| {
+                let message = format!(
+                    "{} `{}` is never referenced",
+                    symbol.data.class,
+                    symbol.name()
+                );
+                finding(symbol, message)
+            })
+            .collect()
+    }
+}
+
+/// Resources are deployed into the `scoped` declaration written before them;
+/// a resource that is neither scoped nor preceded by one has nowhere to go.
+pub struct MissingScope;
+
+impl Lint for MissingScope {
+    fn name(&self) -> &'static str {
+        "missing_scope"
+    }
+
+    fn description(&self) -> &'static str {
+        "Resources that are not scoped and follow no scoped declaration"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check<'a>(&self, context: &LintContext<'a>) -> Vec<Finding<'a>> {
+        let mut findings = Vec::new();
+        for symbol in &context.symbols.symbols {
+            if symbol.data.class != ResourceClass::Custom || symbol.data.is_scoped {
+                continue;
+            }
+            let mut has_scope = false;
+            for statement in &symbol.file.tree.statements {
+                match statement {
+                    Statement::Resource(data) if std::ptr::eq(data, symbol.data) => break,
+                    Statement::Resource(data) if data.is_scoped => has_scope = true,
+                    _ => {}
+                }
+            }
+            if !has_scope {
+                let message = format!(
+                    "resource `{}` is not scoped and no scoped declaration precedes it",
+                    symbol.name()
+                );
+                findings.push(finding(symbol, message));
+            }
+        }
+        findings
+    }
+}
+
+pub struct NamingConvention;
+
+impl Lint for NamingConvention {
+    fn name(&self) -> &'static str {
+        "naming_convention"
+    }
+
+    fn description(&self) -> &'static str {
+        "Declaration names that are not snake_case"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check<'a>(&self, context: &LintContext<'a>) -> Vec<Finding<'a>> {
+        let mut findings = Vec::new();
+        for symbol in &context.symbols.symbols {
+            let name = symbol.name();
+            let expected = snake_case(name);
+            if name != expected {
+                let message = format!(
+                    "{} `{name}` should have a snake_case name: `{expected}`",
+                    symbol.data.class
+                );
+                findings.push(finding(symbol, message));
+            }
+        }
+        findings
+    }
+}
+
+fn finding<'a>(symbol: &Symbol<'a>, message: String) -> Finding<'a> {
+    Finding {
+        data: symbol.data,
+        location: symbol.location(),
+        message,
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c == '-' {
+            result.push('_');
+        } else if c.is_uppercase() {
+            if previous.is_some_and(|x| x.is_lowercase() || x.is_ascii_digit()) {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+        previous = Some(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::symbols::SymbolTable;
+    use crate::inputs::VariableValues;
+    use crate::project::{Project, Sources};
+    use crate::providers::schema::SchemaRegistry;
+
+    /// Messages of a rule for a single-file project.
+    fn findings(rule: &dyn Lint, source: &str) -> Vec<String> {
+        let sources = Sources::from_files(&[("main.land", source)]);
+        let project = Project::parse(&sources).unwrap();
+        let symbols = SymbolTable::build(&project);
+        let expansion = project.expand();
+        let inputs = VariableValues::default();
+        let schemas = SchemaRegistry::new();
+        let context = LintContext {
+            project: &project,
+            symbols: &symbols,
+            expansion: &expansion,
+            inputs: &inputs,
+            schemas: &schemas,
+            environment: None,
+        };
+        let findings = rule.check(&context).into_iter();
+        findings.map(|x| x.message).collect()
+    }
+
+    const SOURCE: &str = r#"
+variable location of string;
+variable sku of string;
+
+resource rg of azure::resource_group scoped {
+  name     = "rg"
+  location = location
+}
+
+resource plan of azure::service_plan {
+  name = "plan"
+}
+
+@depends_on(plan)
+resource app of azure::linux_webapp {
+  name = "app"
+}
+"#;
+
+    #[test]
+    fn finds_unused_variables() {
+        assert_eq!(
+            findings(&UnusedVariable, SOURCE),
+            ["variable `sku` is never used"]
+        );
+    }
+
+    #[test]
+    fn finds_unreferenced_resources() {
+        assert_eq!(
+            findings(&UnreferencedResource, SOURCE),
+            [
+                "resource `rg` is never referenced",
+                "resource `app` is never referenced",
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_resources_without_scope() {
+        let source = r#"
+resource plan of azure::service_plan {
+  name = "plan"
+}
+
+resource rg of azure::resource_group scoped {
+  name = "rg"
+}
+
+resource app of azure::linux_webapp {
+  name = "app"
+}
+"#;
+        assert_eq!(
+            findings(&MissingScope, source),
+            ["resource `plan` is not scoped and no scoped declaration precedes it"]
+        );
+    }
+
+    #[test]
+    fn finds_names_that_are_not_snake_case() {
+        let source = r#"
+variable storageAccount2Name of string;
+output WebURL {
+  value = "url"
+}
+variable snake_case of string;
+"#;
+        assert_eq!(
+            findings(&NamingConvention, source),
+            [
+                "variable `storageAccount2Name` should have a snake_case name: `storage_account2_name`",
+                "output `WebURL` should have a snake_case name: `web_url`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! Security misconfiguration checks for Azure resources.
+//!
+//! Each rule has a stable ID to use in configuration and in
+//! `@lint_allow(...)`, an impact and a remediation hint. Attributes are
+//! checked when their value is known before deployment, for every instance
+//! of the module declaring the resource; a value computed from other
+//! resources is given the benefit of the doubt.
+
+use super::{Finding, Lint, LintContext, Severity};
+use crate::analysis::evaluate::{Evaluator, Scope, Value};
+use crate::analysis::sensitivity::REDACTED;
+use crate::analysis::Location;
+use crate::parsing::syntax_tree::{ResourceClass, ResourceData};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Impact {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Impact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+            Self::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// Value of an attribute as far as it is known before deployment.
+#[derive(Debug, Clone)]
+pub enum Setting {
+    Missing,
+    Known(Value),
+    Unknown,
+}
+
+impl Setting {
+    fn is_true(&self) -> bool {
+        self.bool() == Some(true)
+    }
+
+    fn is_false(&self) -> bool {
+        self.bool() == Some(false)
+    }
+
+    fn bool(&self) -> Option<bool> {
+        match self {
+            Self::Known(value) => value.as_bool(),
+            _ => None,
+        }
+    }
+
+    fn is_sensitive(&self) -> bool {
+        matches!(self, Self::Known(value) if value.is_sensitive())
+    }
+
+    fn string(&self) -> Option<&str> {
+        match self {
+            Self::Known(value) => value.as_str(),
+            _ => None,
+        }
+    }
+}
+
+/// Looks up the setting of an attribute of the checked resource.
+pub type Settings<'a> = dyn Fn(&str) -> Setting + 'a;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SecurityRule {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub impact: Impact,
+    pub remediation: &'static str,
+    pub resource_types: &'static [&'static str],
+    /// Whether the rule only applies to production environments.
+    pub production_only: bool,
+    /// The problem with a resource, if any.
+    pub check: fn(&Settings) -> Option<String>,
+}
+
+impl Lint for SecurityRule {
+    fn name(&self) -> &'static str {
+        self.id
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn default_severity(&self) -> Severity {
+        match self.impact {
+            Impact::Low | Impact::Medium => Severity::Warning,
+            Impact::High | Impact::Critical => Severity::Error,
+        }
+    }
+
+    fn remediation(&self) -> Option<&'static str> {
+        Some(self.remediation)
+    }
+
+    fn check<'a>(&self, context: &LintContext<'a>) -> Vec<Finding<'a>> {
+        if self.production_only && !context.is_production() {
+            return Vec::new();
+        }
+        let evaluator = Evaluator::new(context.project)
+            .with_inputs(context.inputs)
+            .with_schemas(context.schemas);
+        let mut findings = Vec::new();
+        for declaration in &context.expansion.declarations {
+            let data = declaration.data;
+            let type_name = data.type_name.to_string();
+            if data.class != ResourceClass::Custom
+                || !self.resource_types.contains(&type_name.as_str())
+            {
+                continue;
+            }
+            let file = match declaration.module.source_file(data) {
+                Some(file) => file,
+                None => continue,
+            };
+            let scope = Scope::instance(context.project, context.expansion, declaration);
+            let setting = |name: &str| setting(&evaluator, &scope, data, name);
+            if let Some(problem) = (self.check)(&setting) {
+                findings.push(Finding {
+                    data,
+                    location: Location::new(file, data.span),
+                    message: format!(
+                        "[{}] {type_name} `{}` {problem}",
+                        self.impact,
+                        declaration.qualified_name()
+                    ),
+                });
+            }
+        }
+        findings
+    }
+}
+
+fn setting<'a>(
+    evaluator: &Evaluator<'a>,
+    scope: &Scope<'a>,
+    data: &ResourceData,
+    name: &str,
+) -> Setting {
+    match data.attribute(name) {
+        None => Setting::Missing,
+        Some(expression) => match evaluator.evaluate_in(scope, expression) {
+            Ok(value) if value.is_known() => Setting::Known(value),
+            _ => Setting::Unknown,
+        },
+    }
+}
+
+const TLS_VERSIONS: &[&str] = &["1.0", "1.1", "TLS1_0", "TLS1_1"];
+
+const FREE_SKUS: &[&str] = &["F1", "FREE", "Free", "D1", "SHARED", "Shared"];
+
+pub const RULES: &[SecurityRule] = &[
+    SecurityRule {
+        id: "AZU001",
+        description: "SQL servers must require TLS 1.2 or newer",
+        impact: Impact::High,
+        remediation: "Set `minimum_tls_version = \"1.2\"`.",
+        resource_types: &["azure::mssql_server"],
+        production_only: false,
+        check: |setting| match setting("minimum_tls_version") {
+            Setting::Missing => Some(String::from("does not set a minimum TLS version")),
+            x if x.string().is_some_and(|v| TLS_VERSIONS.contains(&v)) => {
+                Some(String::from("accepts TLS versions older than 1.2"))
+            }
+            _ => None,
+        },
+    },
+    SecurityRule {
+        id: "AZU002",
+        description: "Web apps must only be served over HTTPS",
+        impact: Impact::High,
+        remediation: "Set `https_only = true`.",
+        resource_types: &[
+            "azure::linux_function_app",
+            "azure::linux_webapp",
+            "azure::windows_function_app",
+            "azure::windows_webapp",
+        ],
+        production_only: false,
+        check: |setting| match setting("https_only") {
+            Setting::Missing => Some(String::from(
+                "accepts plain HTTP as `https_only` is not set",
+            )),
+            x if x.is_false() => Some(String::from("accepts plain HTTP")),
+            _ => None,
+        },
+    },
+    SecurityRule {
+        id: "AZU003",
+        description: "Data services should not be reachable from the public internet",
+        impact: Impact::Medium,
+        remediation: "Set `public_network_access_enabled = false` and use private endpoints.",
+        resource_types: &[
+            "azure::container_registry",
+            "azure::cosmosdb_account",
+            "azure::key_vault",
+            "azure::mssql_server",
+            "azure::redis_cache",
+            "azure::storage_account",
+        ],
+        production_only: false,
+        check: |setting| match setting("public_network_access_enabled") {
+            Setting::Missing => Some(String::from("allows public network access by default")),
+            x if x.is_true() => Some(String::from("allows public network access")),
+            _ => None,
+        },
+    },
+    SecurityRule {
+        id: "AZU004",
+        description: "Production workloads should not run on free or shared plans",
+        impact: Impact::Medium,
+        remediation: "Use a dedicated SKU such as `B1`, `S1` or `P1v3` for production.",
+        resource_types: &["azure::service_plan"],
+        production_only: true,
+        check: |setting| {
+            let (sku_name, sku) = (setting("sku_name"), setting("sku"));
+            let setting = match sku_name.string() {
+                Some(_) => sku_name,
+                None => sku,
+            };
+            let sku = setting.string()?;
+            let shown = match setting.is_sensitive() {
+                true => REDACTED,
+                false => sku,
+            };
+            match FREE_SKUS.contains(&sku) {
+                true => Some(format!(
+                    "uses the free or shared SKU `{shown}` in production"
+                )),
+                false => None,
+            }
+        },
+    },
+    SecurityRule {
+        id: "AZU005",
+        description: "Storage accounts must require TLS 1.2 or newer",
+        impact: Impact::High,
+        remediation: "Set `min_tls_version = \"TLS1_2\"`.",
+        resource_types: &["azure::storage_account"],
+        production_only: false,
+        check: |setting| match setting("min_tls_version") {
+            x if x.string().is_some_and(|v| TLS_VERSIONS.contains(&v)) => {
+                Some(String::from("accepts TLS versions older than 1.2"))
+            }
+            _ => None,
+        },
+    },
+    SecurityRule {
+        id: "AZU006",
+        description: "Storage accounts should not allow public blob access",
+        impact: Impact::Medium,
+        remediation: "Set `allow_nested_items_to_be_public = false`.",
+        resource_types: &["azure::storage_account"],
+        production_only: false,
+        check: |setting| match setting("allow_nested_items_to_be_public") {
+            Setting::Missing => Some(String::from("allows public blob access by default")),
+            x if x.is_true() => Some(String::from("allows public blob access")),
+            _ => None,
+        },
+    },
+    SecurityRule {
+        id: "AZU007",
+        description: "Key vaults should be protected against purging",
+        impact: Impact::Medium,
+        remediation: "Set `purge_protection_enabled = true`.",
+        resource_types: &["azure::key_vault"],
+        production_only: false,
+        check: |setting| match setting("purge_protection_enabled") {
+            x if x.is_true() => None,
+            Setting::Unknown => None,
+            _ => Some(String::from("can be purged, losing its secrets for good")),
+        },
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::symbols::SymbolTable;
+    use crate::inputs::Inputs;
+    use crate::project::{Project, Sources};
+    use crate::providers::schema::SchemaRegistry;
+
+    const DATABASE: &str = r#"
+variable tls of string;
+
+resource server of azure::mssql_server {
+  name                          = "server"
+  minimum_tls_version           = tls
+  public_network_access_enabled = false
+}
+"#;
+
+    /// Messages of a rule for a project, with the variables set by `flags`.
+    fn findings(id: &str, source: &str, flags: &[&str]) -> Vec<String> {
+        let files = [("main.land", source), ("modules/db/main.land", DATABASE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let mut inputs = Inputs::new();
+        for flag in flags {
+            inputs.load_flag(flag).unwrap();
+        }
+        let inputs = inputs.resolve(&project).unwrap();
+        let symbols = SymbolTable::build(&project);
+        let expansion = project.expand();
+        let schemas = SchemaRegistry::new();
+        let context = LintContext {
+            project: &project,
+            symbols: &symbols,
+            expansion: &expansion,
+            inputs: &inputs,
+            schemas: &schemas,
+            environment: Some(String::from("production")),
+        };
+        let rule = RULES.iter().find(|x| x.id == id).unwrap();
+        let findings = rule.check(&context).into_iter();
+        findings.map(|x| x.message).collect()
+    }
+
+    #[test]
+    fn checks_every_module_instance_with_its_inputs() {
+        let source = r#"
+module shop of land::modules::db { tls = "1.0" }
+module blog of land::modules::db { tls = "1.2" }
+"#;
+        assert_eq!(
+            findings("AZU001", source, &[]),
+            ["[high] azure::mssql_server `shop::server` accepts TLS versions older than 1.2"]
+        );
+    }
+
+    #[test]
+    fn uses_the_given_variable_values() {
+        let source = r#"
+variable sku of string { default = "S1" }
+
+resource plan of azure::service_plan {
+  name = "plan"
+  sku  = sku
+}
+"#;
+        assert!(findings("AZU004", source, &[]).is_empty());
+        assert_eq!(
+            findings("AZU004", source, &["sku=F1"]),
+            ["[medium] azure::service_plan `plan` uses the free or shared SKU `F1` in production"]
+        );
+    }
+}
@@ -1,4 +1,11 @@
+pub mod analysis;
+pub mod annotations;
+pub mod codegen;
+pub mod inputs;
+pub mod lint;
 pub mod parsing;
+pub mod project;
+pub mod providers;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -13,6 +20,8 @@ fn main() {
     match args.get(1) {
         Some(command) => match command.as_str() {
             "validate" => validate(&args[2..]),
+            "lint" => lint(&args[2..]),
+            "build" => build(&args[2..]),
             _ => main_usage(),
         },
         None => main_usage(),
@@ -20,17 +29,337 @@ fn main() {
 }
 
 fn validate(args: &[String]) {
-    use crate::parsing::parse;
+    use crate::analysis::dependencies::DependencyGraph;
+    use crate::analysis::sensitivity::Sensitivity;
+    use crate::analysis::types;
+    use crate::analysis::{
+        declarations::DeclarationIndex, names, naming, symbols::SymbolTable, validations, Location,
+    };
+    use crate::annotations::AnnotationRegistry;
+    use crate::inputs::{Inputs, VariableValues};
+    use crate::project::Project;
+
+    let verbose = args.iter().any(|x| x == "-v" || x == "--verbose");
+
+    let sources = match read_sources(args) {
+        Some(sources) => sources,
+        None => std::process::exit(1),
+    };
+    let mappings = match read_mappings(&sources.root) {
+        Some(mappings) => mappings,
+        None => std::process::exit(1),
+    };
+    let schemas = match read_schemas(args, &sources.root, &mappings) {
+        Some(schemas) => schemas,
+        None => std::process::exit(1),
+    };
+    let project = match Project::parse(&sources) {
+        Ok(project) => project,
+        Err(errors) => {
+            for error in errors {
+                println!("{error}");
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let expansion = project.expand();
+    let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+    if verbose {
+        for module in project.modules() {
+            for file in &module.files {
+                let tree = sensitivity.redacted(module, &file.tree);
+                println!("{}: {:#?}", file.path.display(), tree);
+            }
+        }
+    }
+
+    let registry = AnnotationRegistry::builtin();
+    for module in project.modules() {
+        for file in &module.files {
+            for statement in &file.tree.statements {
+                let location = Location::new(file, statement.span());
+                for warning in registry.check(statement) {
+                    println!("Warning: {location}: {warning}");
+                }
+            }
+        }
+    }
+
+    let symbols = SymbolTable::build(&project);
+    for warning in sensitivity.check() {
+        println!("Warning: {warning}");
+    }
+
+    let (inputs, input_errors, missing_inputs) = match Inputs::load(&sources.root, args) {
+        Ok(inputs) => match inputs.resolve(&project) {
+            Ok(values) => (values, Vec::new(), inputs.missing(&project)),
+            Err(errors) => (VariableValues::default(), errors, inputs.missing(&project)),
+        },
+        Err(errors) => (VariableValues::default(), errors, Vec::new()),
+    };
+
+    let import_errors = project.check_imports();
+    let instance_errors = &expansion.errors;
+    let name_errors = names::check(&project, &symbols, &schemas);
+    let duplicate_errors = DeclarationIndex::build(&project).duplicates();
+    let cycle_errors = DependencyGraph::build(&project, &expansion).check();
+    let type_errors = types::check(&project, &symbols, &schemas);
+    let naming = naming::check(&project, &expansion, &schemas, &inputs);
+    let validation_errors = validations::check(&project, &expansion, &inputs);
+    let is_ok = input_errors.is_empty()
+        && import_errors.is_empty()
+        && instance_errors.is_empty()
+        && name_errors.is_empty()
+        && duplicate_errors.is_empty()
+        && cycle_errors.is_empty()
+        && type_errors.is_empty()
+        && naming.errors.is_empty()
+        && validation_errors.is_empty();
+    if is_ok {
+        println!("Source code is OK");
+    }
+    for error in input_errors {
+        println!("Input Error: {error}");
+    }
+    for error in import_errors {
+        println!("Resolve Error: {error}");
+    }
+    for error in instance_errors {
+        println!("Module Error: {error}");
+    }
+    for error in name_errors {
+        println!("Resolve Error: {error}");
+    }
+    for error in duplicate_errors {
+        println!("Declaration Error: {error}");
+    }
+    for error in cycle_errors {
+        println!("Dependency Error: {error}");
+    }
+    for error in type_errors {
+        println!("Type Error: {error}");
+    }
+    for error in naming.errors {
+        println!("Naming Error: {error}");
+    }
+    for error in validation_errors {
+        println!("Validation Error: {error}");
+    }
+    for hint in naming.hints {
+        println!("Hint: {hint}");
+    }
+    for name in missing_inputs {
+        println!("Hint: no value given for variable `{name}`, it must be set when deploying");
+    }
+    if !is_ok {
+        std::process::exit(1);
+    }
+}
+
+fn lint(args: &[String]) {
+    use crate::analysis::symbols::SymbolTable;
+    use crate::inputs::Inputs;
+    use crate::lint::{LintContext, Linter, Severity};
+    use crate::project::Project;
+
+    let sources = match read_sources(args) {
+        Some(sources) => sources,
+        None => std::process::exit(1),
+    };
+    let mappings = match read_mappings(&sources.root) {
+        Some(mappings) => mappings,
+        None => std::process::exit(1),
+    };
+    let schemas = match read_schemas(args, &sources.root, &mappings) {
+        Some(schemas) => schemas,
+        None => std::process::exit(1),
+    };
+
+    let mut linter = Linter::builtin();
+    if let Err(error) = linter.configure(&sources.root) {
+        println!("Config Error: {error}");
+        std::process::exit(1);
+    }
+    let flags = [
+        ("--allow", Severity::Allow),
+        ("--warn", Severity::Warning),
+        ("--deny", Severity::Error),
+    ];
+    for (flag, rule) in args.iter().zip(args.iter().skip(1)) {
+        let severity = match flags.iter().find(|(x, _)| x == flag) {
+            Some((_, severity)) => *severity,
+            None => continue,
+        };
+        if let Err(error) = linter.set_severity(rule, severity) {
+            println!("Error: {error}");
+            std::process::exit(1);
+        }
+    }
+
+    let environment_index = args.iter().position(|x| x == "--env");
+    if let Some(environment) = environment_index.and_then(|index| args.get(index + 1)) {
+        linter.environment = Some(environment.clone());
+    }
+
+    if args.iter().any(|x| x == "--list") {
+        for rule in linter.rules() {
+            let severity = linter.severity(rule);
+            println!("{:<24}{:<10}{}", rule.name(), severity, rule.description());
+        }
+        return;
+    }
+
+    let project = match Project::parse(&sources) {
+        Ok(project) => project,
+        Err(errors) => {
+            for error in errors {
+                println!("{error}");
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let inputs = Inputs::load(&sources.root, args).and_then(|x| x.resolve(&project));
+    let inputs = match inputs {
+        Ok(inputs) => inputs,
+        Err(errors) => {
+            for error in errors {
+                println!("Input Error: {error}");
+            }
+            std::process::exit(1);
+        }
+    };
+
+    let symbols = SymbolTable::build(&project);
+    let expansion = project.expand();
+    let context = LintContext {
+        project: &project,
+        symbols: &symbols,
+        expansion: &expansion,
+        inputs: &inputs,
+        schemas: &schemas,
+        environment: linter.environment.clone(),
+    };
+    let diagnostics = linter.run(&context);
+    if diagnostics.is_empty() {
+        println!("No lint findings");
+    }
+    let has_errors = diagnostics.iter().any(|x| x.severity == Severity::Error);
+    for diagnostic in diagnostics {
+        println!("{diagnostic}");
+    }
+    if has_errors {
+        std::process::exit(1);
+    }
+}
+
+fn build(args: &[String]) {
+    use crate::analysis::sensitivity::Sensitivity;
+    use crate::codegen::{self, bicep, hcl, json};
+    use crate::project::Project;
+
+    let target_index = args.iter().position(|x| x == "-t" || x == "--target");
+    let target = match target_index.map(|index| args.get(index + 1)) {
+        None => "terraform",
+        Some(Some(target)) => target.as_str(),
+        Some(None) => {
+            println!("Error: Build target should be defined!");
+            return;
+        }
+    };
+    let write: Option<fn(&codegen::Configuration) -> _> = match target {
+        "terraform" => Some(hcl::write),
+        "terraform-json" => Some(json::write),
+        "bicep" => None,
+        _ => {
+            println!(
+                "Error: Unknown build target `{target}`, expected `terraform`, `terraform-json` or `bicep`"
+            );
+            return;
+        }
+    };
+
+    let sources = match read_sources(args) {
+        Some(sources) => sources,
+        None => return,
+    };
+    let mappings = match read_mappings(&sources.root) {
+        Some(mappings) => mappings,
+        None => return,
+    };
+    let schemas = match read_schemas(args, &sources.root, &mappings) {
+        Some(schemas) => schemas,
+        None => return,
+    };
+    let project = match Project::parse(&sources) {
+        Ok(project) => project,
+        Err(errors) => {
+            for error in errors {
+                println!("{error}");
+            }
+            return;
+        }
+    };
+
+    let import_errors = project.check_imports();
+    let expansion = project.expand();
+    if !import_errors.is_empty() || !expansion.errors.is_empty() {
+        for error in import_errors {
+            println!("Resolve Error: {error}");
+        }
+        for error in &expansion.errors {
+            println!("Module Error: {error}");
+        }
+        return;
+    }
+
+    let sensitivity = Sensitivity::new(&project, &expansion, &schemas);
+    let files = match write {
+        Some(write) => {
+            codegen::lower(&project, &expansion, &sensitivity, &mappings).map(|x| write(&x))
+        }
+        None => bicep::write(&project, &expansion, &sensitivity, &mappings),
+    };
+    let files = match files {
+        Ok(files) => files,
+        Err(errors) => {
+            for error in errors {
+                println!("Build Error: {error}");
+            }
+            return;
+        }
+    };
+
+    let out_index = args.iter().position(|x| x == "-o" || x == "--out");
+    let out_dir = match out_index.and_then(|index| args.get(index + 1)) {
+        Some(path) => std::path::PathBuf::from(path),
+        None => sources.root.join("build").join(target),
+    };
+    if let Err(error) = std::fs::create_dir_all(&out_dir) {
+        println!("Stream Error: {}: {error}", out_dir.display());
+        return;
+    }
+    for (name, text) in files {
+        let path = out_dir.join(name);
+        match std::fs::write(&path, text) {
+            Ok(()) => println!("Wrote {}", path.display()),
+            Err(error) => println!("Stream Error: {}: {error}", path.display()),
+        }
+    }
+}
+
+/// Reads the project sources from the working directory, or from standard
+/// input with `--stdin`. Errors are printed and give `None`.
+fn read_sources(args: &[String]) -> Option<crate::project::Sources> {
+    use crate::project::Sources;
     use std::io::Read;
 
     let prefer_stdin = args.iter().any(|x| x == "--stdin");
-    let verbose = args.iter().any(|x| x == "-v" || x == "--verbose");
 
     let project_dir_index = args.iter().position(|x| x == "-w" || x == "--workdir");
     let project_dir = match project_dir_index {
-        None => std::env::current_dir()
-            .map(|path| path)
-            .map_err(|_| "Current directory could not be detected"),
+        None => std::env::current_dir().map_err(|_| "Current directory could not be detected"),
         Some(index) => match args.get(index + 1) {
             Some(path_str) => Ok(std::path::Path::new(path_str).to_path_buf()),
             None => Err("Working directory path should be defined!"),
@@ -39,59 +368,88 @@ fn validate(args: &[String]) {
 
     if let Err(message) = project_dir {
         println!("Error: {message}");
-        return;
+        return None;
     }
 
-    let mut buffer = String::new();
-    if !prefer_stdin {
-        let readers =
-            std::fs::read_dir(project_dir.unwrap())
-                .unwrap()
-                .filter(|entry| match entry {
-                    Err(_) => false,
-                    Ok(entry) => match entry.path().extension() {
-                        None => false,
-                        Some(ext) => ext.to_str() == Some("land"),
-                    },
-                });
-
-        for entry in readers {
-            let entry = entry.unwrap();
-            let mut file = std::fs::File::open(entry.path()).unwrap();
-            match file.read_to_string(&mut buffer) {
-                Ok(_) => match parse(&buffer) {
-                    Ok(tree) => {
-                        if verbose {
-                            println!("{tree:#?}");
-                        }
-                        println!("Source code is OK");
-                    }
-                    Err(error) => println!("Parse Error: {error}"),
-                },
-                Err(error) => println!("Stream Error: {error}"),
+    let sources = if !prefer_stdin {
+        Sources::load(&project_dir.unwrap())
+    } else {
+        let mut buffer = String::new();
+        match std::io::stdin().read_to_string(&mut buffer) {
+            Ok(_) => Ok(Sources::from_text(
+                std::path::PathBuf::from("<stdin>"),
+                buffer,
+            )),
+            Err(error) => {
+                println!("Stream Error: {error}");
+                return None;
             }
         }
-    } else {
-        let mut reader = std::io::stdin();
-        match reader.read_to_string(&mut buffer) {
-            Ok(_) => match parse(&buffer) {
-                Ok(tree) => {
-                    if verbose {
-                        println!("{tree:#?}");
-                    }
-                    println!("Source code is OK");
-                }
-                Err(error) => println!("Parse Error: {error}"),
-            },
-            Err(error) => println!("Stream Error: {error}"),
+    };
+
+    match sources {
+        Ok(sources) => Some(sources),
+        Err(errors) => {
+            for error in errors {
+                println!("{error}");
+            }
+            None
+        }
+    }
+}
+
+/// Reads the provider mappings shipped with landlord and those of the
+/// project. Errors are printed and give `None`.
+fn read_mappings(root: &std::path::Path) -> Option<crate::providers::mappings::Mappings> {
+    use crate::providers::mappings::Mappings;
+
+    match Mappings::load_project(root) {
+        Ok(mappings) => Some(mappings),
+        Err(error) => {
+            println!("Mapping Error: {error}");
+            None
+        }
+    }
+}
+
+/// Loads the provider schemas of the project and those given with
+/// `--schema`. Errors are printed and give `None`.
+fn read_schemas(
+    args: &[String],
+    root: &std::path::Path,
+    mappings: &crate::providers::mappings::Mappings,
+) -> Option<crate::providers::schema::SchemaRegistry> {
+    use crate::providers::schema::SchemaRegistry;
+
+    let mut schemas = match SchemaRegistry::load_project(root, mappings) {
+        Ok(schemas) => schemas,
+        Err(errors) => {
+            for error in errors {
+                println!("{error}");
+            }
+            return None;
+        }
+    };
+    let schema_files = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .filter(|(flag, _)| *flag == "--schema")
+        .map(|(_, path)| std::path::Path::new(path));
+    for file in schema_files {
+        if let Err(error) = schemas.load_file(file, mappings) {
+            println!("{error}");
+            return None;
         }
     }
+    Some(schemas)
 }
 
 fn usage(command: Option<&String>) {
     match command {
         Some(command) => match command.as_str() {
             "validate" => validate_usage(),
+            "lint" => lint_usage(),
+            "build" => build_usage(),
             _ => main_usage(),
         },
         None => main_usage(),
@@ -112,6 +470,8 @@ OPTIONS:
 
 COMMANDS:
     validate    Validate given source code
+    lint        Check source code for likely mistakes
+    build       Generate deployment configuration from source code
 "###
     );
 }
@@ -130,6 +490,69 @@ OPTIONS:
         --stdin             Prefer taking source code from standard input
     -w, --workdir <path>    Set working directory to validate (default: current directory)
     -v, --verbose           Print syntax tree
+        --schema <path>     Load a provider schema from a JSON file (can be repeated)
+        --var <name=value>  Set the value of a variable (can be repeated)
+
+Variables take their values from, in increasing order of precedence, their
+default, *.landvars files in the project root, LANDLORD_VAR_<name>
+environment variables and --var flags. The exit status is 1 when an error
+is reported.
+"###
+    );
+}
+
+fn lint_usage() {
+    print!(
+        r###"landlord lint - version {VERSION:?}
+
+Check source code for likely mistakes
+
+USAGE:
+    landlord lint [options]
+
+OPTIONS:
+    -h, --help              Print help information
+        --stdin             Prefer taking source code from standard input
+    -w, --workdir <path>    Set working directory to lint (default: current directory)
+        --schema <path>     Load a provider schema from a JSON file (can be repeated)
+        --list              List the lint rules and their severity
+        --allow <rule>      Turn off a rule
+        --warn <rule>       Report findings of a rule as warnings
+        --deny <rule>       Report findings of a rule as errors
+        --env <name>        Set the environment, e.g. production, for rules depending on it
+        --var <name=value>  Set the value of a variable (can be repeated)
+
+Rule severities can also be set in .landlord/lint.json, and a declaration
+can opt out of rules with @lint_allow(rule, ...). The exit status is 1 when
+a finding is reported as an error.
+"###
+    );
+}
+
+fn build_usage() {
+    print!(
+        r###"landlord build - version {VERSION:?}
+
+Generate deployment configuration from source code
+
+USAGE:
+    landlord build [options]
+
+OPTIONS:
+    -h, --help              Print help information
+        --stdin             Prefer taking source code from standard input
+    -w, --workdir <path>    Set working directory to build (default: current directory)
+        --schema <path>     Load a provider schema from a JSON file (can be repeated)
+    -t, --target <name>     Set the kind of configuration to generate (default: terraform)
+    -o, --out <path>        Set the directory to write to (default: build/<target> in the project)
+
+TARGETS:
+    terraform       Terraform configuration files (.tf)
+    terraform-json  Terraform configuration files in JSON syntax (.tf.json)
+    bicep           Azure Bicep files, for `azure` resources only (.bicep)
+
+Names of provider types and attributes in each target can be changed or
+added in .landlord/mappings.json.
 "###
     );
 }
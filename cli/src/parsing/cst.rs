@@ -0,0 +1,310 @@
+//! Lossless concrete syntax tree.
+//!
+//! The source is split into tokens, whitespace included, and the tokens are
+//! grouped into statements and bracketed groups. Writing the tree back out
+//! gives the original source byte for byte, which is what formatters and
+//! automated refactors need. Each statement of the abstract
+//! [`super::syntax_tree`] is parsed from the text of a statement node of
+//! this tree, so statements can be parsed again on their own.
+
+use super::keywords;
+use super::syntax_tree::Span;
+use std::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while1},
+    character::complete::{anychar, char, multispace1, not_line_ending, satisfy},
+    combinator::{map, opt, recognize},
+    multi::many0_count,
+    sequence::{pair, preceded, tuple},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Whitespace,
+    DocComment,
+    Word,
+    String,
+    Punctuation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'src> {
+    pub kind: TokenKind,
+    pub text: &'src str,
+    pub offset: usize,
+}
+
+impl<'src> Token<'src> {
+    pub fn span(&self) -> Span {
+        Span {
+            start: self.offset,
+            end: self.offset + self.text.len(),
+        }
+    }
+
+    pub fn is_trivia(&self) -> bool {
+        self.kind == TokenKind::Whitespace
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    SourceFile,
+    Statement,
+    /// Tokens between matching `{}`, `[]` or `()`, delimiters included.
+    Group,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Element<'src> {
+    Node(Node<'src>),
+    Token(Token<'src>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node<'src> {
+    pub kind: NodeKind,
+    pub children: Vec<Element<'src>>,
+}
+
+impl<'src> Element<'src> {
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Node(node) => node.span(),
+            Self::Token(token) => Some(token.span()),
+        }
+    }
+}
+
+impl<'src> Node<'src> {
+    pub fn new(kind: NodeKind) -> Node<'src> {
+        Node {
+            kind,
+            children: Vec::new(),
+        }
+    }
+
+    /// Tokens of the node and all its descendants, in source order.
+    pub fn tokens(&self) -> Vec<&Token<'src>> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match child {
+                Element::Node(node) => tokens.extend(node.tokens()),
+                Element::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        let tokens = self.tokens();
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => Some(Span {
+                start: first.offset,
+                end: last.offset + last.text.len(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether the node ends like a complete statement, with a `;` or with
+    /// the `}` closing its body.
+    pub fn is_terminated(&self) -> bool {
+        let last = match self.children.last() {
+            Some(Element::Token(token)) => return token.text == ";",
+            Some(Element::Node(group)) => group.tokens().last().map(|token| token.text),
+            None => None,
+        };
+        last == Some("}")
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = &Node<'src>> {
+        self.children.iter().filter_map(|child| match child {
+            Element::Node(node) if node.kind == NodeKind::Statement => Some(node),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.tokens() {
+            write!(f, "{}", token.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits the whole input into tokens. Every byte of the input belongs to
+/// exactly one token.
+pub fn tokenize(input: &str) -> Vec<Token<'_>> {
+    tokenize_range(input, whole(input))
+}
+
+/// Splits `range` of the input into tokens, keeping offsets relative to the
+/// whole input.
+pub fn tokenize_range(input: &str, range: Span) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = &input[range.start..range.end];
+    while !rest.is_empty() {
+        let (next, (kind, text)) = match token(rest) {
+            Ok(result) => result,
+            Err(_) => break,
+        };
+        tokens.push(Token {
+            kind,
+            text,
+            offset: range.end - rest.len(),
+        });
+        rest = next;
+    }
+    tokens
+}
+
+fn whole(input: &str) -> Span {
+    Span {
+        start: 0,
+        end: input.len(),
+    }
+}
+
+fn token(input: &str) -> nom::IResult<&str, (TokenKind, &str)> {
+    let whitespace = map(multispace1, |text| (TokenKind::Whitespace, text));
+    let doc_comment = map(recognize(pair(tag("///"), not_line_ending)), |text| {
+        (TokenKind::DocComment, text)
+    });
+    let string = map(string, |text| (TokenKind::String, text));
+    let word = map(take_while1(keywords::is_identifier_char), |text| {
+        (TokenKind::Word, text)
+    });
+    let punctuation = map(recognize(anychar), |text| (TokenKind::Punctuation, text));
+    alt((whitespace, doc_comment, string, word, punctuation))(input)
+}
+
+fn string(input: &str) -> nom::IResult<&str, &str> {
+    use super::parsers::{is_escapable_char, is_valid_singleline_string_char};
+    let quote = || alt((char('"'), char('\'')));
+    let plain_character = satisfy(is_valid_singleline_string_char);
+    let escaped_character = preceded(char('\\'), satisfy(is_escapable_char));
+    let characters = many0_count(alt((plain_character, escaped_character)));
+    recognize(tuple((quote(), characters, opt(quote()))))(input)
+}
+
+/// Builds the concrete syntax tree of a source file.
+///
+/// A statement starts at its first non-whitespace token (doc comments and
+/// annotations included) and ends with a `;` or with the `}` closing its
+/// body. Whitespace between statements belongs to the source file node.
+pub fn parse(input: &str) -> Node<'_> {
+    let file = parse_range(input, whole(input));
+    file.unwrap_or_else(|| Node::new(NodeKind::SourceFile))
+}
+
+/// Builds the concrete syntax tree of a part of a source file, as done when
+/// reparsing the statements touched by an edit. Gives `None` when the range
+/// is not one of the input, on character boundaries.
+pub fn parse_range(input: &str, range: Span) -> Option<Node<'_>> {
+    input.get(range.start..range.end)?;
+    let mut file = Node::new(NodeKind::SourceFile);
+    let mut statement: Option<Node> = None;
+    let mut groups: Vec<Node> = Vec::new();
+
+    for token in tokenize_range(input, range) {
+        let current = match statement.as_mut() {
+            Some(current) => current,
+            None if token.is_trivia() => {
+                file.children.push(Element::Token(token));
+                continue;
+            }
+            None => statement.insert(Node::new(NodeKind::Statement)),
+        };
+
+        let is_punctuation = token.kind == TokenKind::Punctuation;
+        let mut ends_statement = false;
+        match token.text {
+            "{" | "[" | "(" if is_punctuation => {
+                let mut group = Node::new(NodeKind::Group);
+                group.children.push(Element::Token(token));
+                groups.push(group);
+            }
+            "}" | "]" | ")" if is_punctuation && closes(groups.last(), token.text) => {
+                let mut group = groups.pop().unwrap();
+                group.children.push(Element::Token(token));
+                match groups.last_mut() {
+                    Some(parent) => parent.children.push(Element::Node(group)),
+                    None => {
+                        current.children.push(Element::Node(group));
+                        ends_statement = token.text == "}";
+                    }
+                }
+            }
+            ";" if is_punctuation && groups.is_empty() => {
+                current.children.push(Element::Token(token));
+                ends_statement = true;
+            }
+            _ => match groups.last_mut() {
+                Some(group) => group.children.push(Element::Token(token)),
+                None => current.children.push(Element::Token(token)),
+            },
+        }
+
+        if ends_statement {
+            file.children.push(Element::Node(statement.take().unwrap()));
+        }
+    }
+
+    if let Some(mut current) = statement {
+        while let Some(group) = groups.pop() {
+            match groups.last_mut() {
+                Some(parent) => parent.children.push(Element::Node(group)),
+                None => current.children.push(Element::Node(group)),
+            }
+        }
+        file.children.push(Element::Node(current));
+    }
+
+    Some(file)
+}
+
+fn closes(group: Option<&Node>, closing: &str) -> bool {
+    let opening = match group.and_then(|group| group.children.first()) {
+        Some(Element::Token(token)) => token.text,
+        _ => return false,
+    };
+    matches!((opening, closing), ("{", "}") | ("[", "]") | ("(", ")"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_the_source() {
+        let sources = [
+            include_str!("../../examples/projects/azure-webapps/main.land"),
+            "/// Doc comment\n@depends_on(a)\nresource b of c {\n\tx = [1, (2)]\n}\n\n",
+            "variable name {\n  default = \"caf\u{e9} \\\" }\"\n}",
+            "  resource unterminated of a { x = \"open",
+            "}) ; ;\n",
+        ];
+        for source in sources {
+            assert_eq!(parse(source).to_string(), source);
+        }
+    }
+
+    #[test]
+    fn groups_statements() {
+        let source = "provider main of azure;\n\nresource rg of azure::resource_group {\n}\n";
+        let file = parse(source);
+        let statements = file.statements().map(Node::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            statements,
+            [
+                "provider main of azure;",
+                "resource rg of azure::resource_group {\n}"
+            ]
+        );
+    }
+}
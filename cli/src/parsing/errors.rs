@@ -0,0 +1,37 @@
+use nom::error::{ErrorKind, ParseError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxErrorKind {
+    Nom(ErrorKind),
+    ReservedKeyword(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError<I> {
+    pub input: I,
+    pub kind: SyntaxErrorKind,
+}
+
+impl<I> ParseError<I> for SyntaxError<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        SyntaxError {
+            input,
+            kind: SyntaxErrorKind::Nom(kind),
+        }
+    }
+
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<I> SyntaxError<I> {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            SyntaxErrorKind::Nom(_) => String::from("Could not parse correctly"),
+            SyntaxErrorKind::ReservedKeyword(keyword) => format!(
+                "`{keyword}` is a reserved keyword and cannot be used as a name (write `r#{keyword}` to use it anyway)"
+            ),
+        }
+    }
+}
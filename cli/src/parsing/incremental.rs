@@ -0,0 +1,278 @@
+//! Incremental reparsing for editor integrations.
+//!
+//! After an edit only the statements touching the edited range are parsed
+//! again. Every other statement is moved over to the new text as it is,
+//! borrowing the same bytes at their new position.
+
+use super::syntax_tree::{MapText, Span, Statement, SyntaxTree};
+use super::{cst, lower_statement};
+use std::borrow::Cow;
+
+/// Replacement of a byte range of the previous text.
+#[derive(Debug, Clone)]
+pub struct TextEdit {
+    pub range: Span,
+    pub text: String,
+}
+
+impl TextEdit {
+    /// The text with the edit applied, or an error when the range is not
+    /// one of the text, on character boundaries.
+    pub fn apply(&self, text: &str) -> Result<String, String> {
+        let before = text.get(..self.range.start);
+        let after = text.get(self.range.end..);
+        match (before, after) {
+            (Some(before), Some(after)) if self.range.start <= self.range.end => {
+                Ok([before, &self.text, after].concat())
+            }
+            _ => Err(String::from("Edit does not match the previous text.")),
+        }
+    }
+
+    fn delta(&self) -> isize {
+        self.text.len() as isize - (self.range.end - self.range.start) as isize
+    }
+}
+
+#[derive(Debug)]
+pub struct Reparse<'src> {
+    pub tree: SyntaxTree<'src>,
+    /// Ranges of the new text covered by reparsed statements.
+    pub changed: Vec<Span>,
+    /// Range of the new text that was reparsed, including whitespace
+    /// around the reparsed statements.
+    pub region: Span,
+}
+
+/// Updates `previous`, parsed from `old_text`, to match `new_text`, which is
+/// `old_text` with `edit` applied.
+pub fn reparse<'new>(
+    previous: &SyntaxTree<'_>,
+    old_text: &str,
+    new_text: &'new str,
+    edit: &TextEdit,
+) -> Result<Reparse<'new>, String> {
+    let mismatch = || String::from("Edit does not match the new text.");
+    let inserted = new_text.get(edit.range.start..edit.range.start + edit.text.len());
+    let old_length = old_text.len().checked_add_signed(edit.delta());
+    if edit.apply(old_text).is_err()
+        || inserted != Some(edit.text.as_str())
+        || old_length != Some(new_text.len())
+    {
+        return Err(mismatch());
+    }
+
+    let spans = previous
+        .statements
+        .iter()
+        .map(Statement::span)
+        .collect::<Vec<_>>();
+    let delta = edit.delta();
+
+    let first = spans
+        .iter()
+        .position(|span| span.end >= edit.range.start)
+        .unwrap_or(spans.len());
+    let mut after = spans
+        .iter()
+        .position(|span| span.start > edit.range.end)
+        .unwrap_or(spans.len());
+
+    let start = match first {
+        0 => 0,
+        index => spans[index - 1].end,
+    };
+
+    let (region, file) = loop {
+        let old_end = spans.get(after).map_or(old_text.len(), |span| span.start);
+        let region = Span {
+            start,
+            end: shift(old_end, delta).ok_or("Edit does not match the previous text.")?,
+        };
+        let file = cst::parse_range(new_text, region).ok_or_else(mismatch)?;
+        let is_complete = file
+            .statements()
+            .last()
+            .is_none_or(cst::Node::is_terminated);
+        if is_complete || after == spans.len() {
+            break (region, file);
+        }
+        after += 1;
+    };
+
+    let reparsed = file
+        .statements()
+        .map(|node| lower_statement(new_text, node))
+        .collect::<Result<Vec<_>, String>>()?;
+    let changed = reparsed.iter().map(Statement::span).collect();
+
+    let before = previous.statements[..first]
+        .iter()
+        .map(|statement| relocate(statement, old_text, new_text, 0));
+    let following = previous.statements[after..]
+        .iter()
+        .map(|statement| relocate(statement, old_text, new_text, delta));
+    let statements = before.chain(reparsed).chain(following).collect();
+
+    Ok(Reparse {
+        tree: SyntaxTree { statements },
+        changed,
+        region,
+    })
+}
+
+/// Moves a statement parsed from `old_text` to the same text shifted by
+/// `delta` bytes in `new_text`, borrowing its names and strings from the
+/// statement text at its new span where possible.
+fn relocate<'new>(
+    statement: &Statement<'_>,
+    old_text: &str,
+    new_text: &'new str,
+    delta: isize,
+) -> Statement<'new> {
+    let span = statement.span();
+    let moved = Span {
+        start: shift(span.start, delta).unwrap_or(span.start),
+        end: shift(span.end, delta).unwrap_or(span.end),
+    };
+    let old_statement = old_text.get(span.start..span.end);
+    let new_statement = new_text.get(moved.start..moved.end);
+    let source = match (old_statement, new_statement) {
+        (Some(old), Some(new)) if old == new => new,
+        _ => "",
+    };
+
+    let relocated = statement.map_text(&mut |text| {
+        let found = source.find(text.as_ref());
+        match found.map(|start| &source[start..start + text.len()]) {
+            Some(slice) if !text.is_empty() => Cow::Borrowed(slice),
+            _ => Cow::Owned(text.to_string()),
+        }
+    });
+    relocated.with_span(moved)
+}
+
+fn shift(offset: usize, delta: isize) -> Option<usize> {
+    offset.checked_add_signed(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parse;
+
+    const SOURCE: &str = r#"provider main of azure scoped;
+
+variable sku {
+  default = "F1"
+}
+
+resource rg of azure::resource_group scoped {
+  name = "landlord-rg"
+}
+
+resource plan of azure::service_plan {
+  sku = sku
+}
+"#;
+
+    fn edit(old_text: &str, find: &str, text: &str) -> TextEdit {
+        let start = old_text.find(find).unwrap();
+        TextEdit {
+            range: Span {
+                start,
+                end: start + find.len(),
+            },
+            text: text.to_string(),
+        }
+    }
+
+    /// Reparses `SOURCE` after the edit and checks the result against a
+    /// full parse of the new text. Bodies have a single attribute, so that
+    /// they are written out in the same order.
+    fn assert_reparsed(edit: TextEdit) {
+        let previous = parse(SOURCE).unwrap();
+        let new_text = edit.apply(SOURCE).unwrap();
+        let reparse = reparse(&previous, SOURCE, &new_text, &edit).unwrap();
+        let full = parse(&new_text).unwrap();
+        assert_eq!(format!("{:?}", reparse.tree), format!("{full:?}"));
+    }
+
+    #[test]
+    fn insert_within_statement() {
+        assert_reparsed(edit(SOURCE, "F1", "Standard_F1"));
+    }
+
+    #[test]
+    fn insert_statement() {
+        let text = "resource ai of azure::application_insights {\n}\n\n";
+        let start = SOURCE.find("resource plan").unwrap();
+        let range = Span { start, end: start };
+        assert_reparsed(TextEdit {
+            range,
+            text: text.to_string(),
+        });
+    }
+
+    #[test]
+    fn delete_statement() {
+        let start = SOURCE.find("resource rg").unwrap();
+        let end = SOURCE.find("resource plan").unwrap();
+        assert_reparsed(TextEdit {
+            range: Span { start, end },
+            text: String::new(),
+        });
+    }
+
+    #[test]
+    fn edit_across_statements() {
+        let start = SOURCE.find("\"F1\"").unwrap();
+        let end = SOURCE.find("resource_group").unwrap();
+        assert_reparsed(TextEdit {
+            range: Span { start, end },
+            text: String::from("\"B1\"\n}\n\nresource rg of azure::"),
+        });
+    }
+
+    #[test]
+    fn edit_breaking_a_statement_is_an_error() {
+        let previous = parse(SOURCE).unwrap();
+        let edit = edit(SOURCE, "\"landlord-rg\"\n}", "\"landlord-rg\"\n");
+        let new_text = edit.apply(SOURCE).unwrap();
+        assert!(parse(&new_text).is_err());
+        assert!(reparse(&previous, SOURCE, &new_text, &edit).is_err());
+    }
+
+    #[test]
+    fn mismatched_edit_is_an_error() {
+        let previous = parse(SOURCE).unwrap();
+        let edit = edit(SOURCE, "F1", "S1");
+        assert!(reparse(&previous, SOURCE, SOURCE, &edit).is_err());
+
+        let range = Span {
+            start: SOURCE.len(),
+            end: SOURCE.len() + 10,
+        };
+        let edit = TextEdit {
+            range,
+            text: String::new(),
+        };
+        assert!(edit.apply(SOURCE).is_err());
+        assert!(reparse(&previous, SOURCE, SOURCE, &edit).is_err());
+    }
+
+    #[test]
+    fn edit_inside_a_character_is_an_error() {
+        let old_text = "variable name {\n  default = \"é\"\n}\n";
+        let previous = parse(old_text).unwrap();
+        let start = old_text.find('é').unwrap() + 1;
+        let edit = TextEdit {
+            range: Span { start, end: start },
+            text: String::from("x"),
+        };
+        assert!(edit.apply(old_text).is_err());
+        let new_text = "variable name {\n  default = \"éx\"\n}\n";
+        assert!(reparse(&previous, old_text, new_text, &edit).is_err());
+        assert!(cst::parse_range(old_text, edit.range).is_none());
+    }
+}
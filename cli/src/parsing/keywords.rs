@@ -0,0 +1,18 @@
+/// Words with a meaning of their own in landlord sources.
+///
+/// They cannot be used as names unless written as raw identifiers, e.g.
+/// `r#type`.
+pub const KEYWORDS: &[&str] = &[
+    "data", "false", "land", "module", "of", "output", "provider", "resource", "scoped", "super",
+    "true", "type", "use", "variable",
+];
+
+pub const RAW_IDENTIFIER_PREFIX: &str = "r#";
+
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.contains(&word)
+}
+
+pub fn is_identifier_char(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
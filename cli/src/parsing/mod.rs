@@ -1,9 +1,60 @@
+pub mod cst;
+pub mod errors;
+pub mod incremental;
+pub mod keywords;
 pub mod parsers;
 pub mod syntax_tree;
 
-pub fn parse(input: &str) -> Result<syntax_tree::SyntaxTree, String> {
-    match parsers::syntax_tree(input) {
-        Ok((_, result)) => Ok(result),
-        Err(_) => Err(String::from("Could not parse correctly.")),
+/// Parses a source file into its syntax tree.
+pub fn parse(input: &str) -> Result<syntax_tree::SyntaxTree<'_>, String> {
+    lower(input, &cst::parse(input))
+}
+
+/// Derives the syntax tree from the concrete syntax tree of `input`, parsing
+/// each statement node on its own.
+pub fn lower<'src>(
+    input: &'src str,
+    file: &cst::Node<'src>,
+) -> Result<syntax_tree::SyntaxTree<'src>, String> {
+    let statements = file
+        .statements()
+        .map(|node| lower_statement(input, node))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(syntax_tree::SyntaxTree { statements })
+}
+
+pub fn lower_statement<'src>(
+    input: &'src str,
+    node: &cst::Node<'src>,
+) -> Result<syntax_tree::Statement<'src>, String> {
+    let span = node.span().unwrap_or_default();
+    let text = &input[span.start..span.end];
+    match parsers::statement(text) {
+        Ok(("", statement)) => Ok(statement.with_span(span)),
+        Ok((rest, _)) => {
+            let offset = span.end - rest.len();
+            let (line, column) = line_column(input, offset);
+            Err(format!(
+                "Could not parse correctly at line {line}, column {column}."
+            ))
+        }
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => {
+            let offset = span.end - error.input.len();
+            let (line, column) = line_column(input, offset);
+            Err(format!(
+                "{} at line {line}, column {column}.",
+                error.message()
+            ))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(String::from("Could not parse correctly.")),
     }
 }
+
+/// One-based line and column of a byte offset in `input`.
+pub fn line_column(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
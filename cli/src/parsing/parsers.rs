@@ -1,11 +1,16 @@
+use super::errors::{SyntaxError, SyntaxErrorKind};
+use super::keywords;
 use super::syntax_tree::{self, ReferencePath};
+use std::borrow::Cow;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, digit1, line_ending, multispace0, multispace1, satisfy, space0},
-    combinator::{eof, map, opt, value},
-    multi::{many0, many1, separated_list0, separated_list1},
+    bytes::complete::{tag, take_while},
+    character::complete::{
+        char, digit1, line_ending, multispace0, multispace1, not_line_ending, satisfy, space0,
+    },
+    combinator::{map, not, opt, recognize, value},
+    multi::{many0, many0_count, many1, separated_list0, separated_list1},
     sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
 };
 
@@ -24,11 +29,10 @@ where
 {
     let opening_brace_prefix = tuple((multispace0, char('{'), multispace0));
     let closing_brace_suffix = tuple((multispace0, char('}'), multispace0));
-    let target = delimited(opening_brace_prefix, parser, closing_brace_suffix);
-    target
+    delimited(opening_brace_prefix, parser, closing_brace_suffix)
 }
 
-pub fn statement_termination<I>(input: I) -> nom::IResult<I, ()>
+pub fn statement_termination<I>(input: I) -> nom::IResult<I, (), SyntaxError<I>>
 where
     I: Clone
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -71,12 +75,71 @@ where
     ));
 
     let entry_list = separated_list0(entry_separator, entry);
-    let hashmap = map(entry_list, |list: Vec<(KO, VO)>| {
+    map(entry_list, |list: Vec<(KO, VO)>| {
         list.into_iter()
             .collect::<std::collections::HashMap<KO, VO>>()
-    });
+    })
+}
+
+/// Entries of a declaration body: attributes written as `name = value`
+/// and nested blocks written as `name { ... }`.
+#[allow(clippy::type_complexity)]
+pub fn body_entries<'src, I>(
+    input: I,
+) -> nom::IResult<
+    I,
+    (
+        syntax_tree::ResourceBody<'src>,
+        Vec<syntax_tree::NestedBlock<'src>>,
+    ),
+    SyntaxError<I>,
+>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let key_value_separator = tuple((multispace0, char('='), multispace0));
+    let attribute = map(
+        separated_pair(name_identifier, key_value_separator, expression),
+        |(key, value)| (Some((key, value)), None),
+    );
+
+    // The closing brace is not followed by whitespace, which is left for
+    // the entry separator.
+    let opening_brace = tuple((multispace0, char('{'), multispace0));
+    let closing_brace = pair(multispace0, char('}'));
+    let block_body = delimited(opening_brace, body_entries, closing_brace);
+    let block = map(
+        pair(name_identifier, block_body),
+        |(name, (body, blocks))| {
+            let block = syntax_tree::NestedBlock { name, body, blocks };
+            (None, Some(block))
+        },
+    );
+
+    let entry_separator = alt((
+        value((), tuple((space0, char(','), space0))),
+        value((), many1(tuple((space0, line_ending, space0)))),
+    ));
 
-    hashmap
+    let entry_list = separated_list0(entry_separator, alt((attribute, block)));
+    let mut entries = map(entry_list, |list| {
+        let (attributes, blocks): (Vec<_>, Vec<_>) = list.into_iter().unzip();
+        let attributes = attributes.into_iter().flatten().collect();
+        (attributes, blocks.into_iter().flatten().collect())
+    });
+    entries(input)
 }
 
 pub fn tagged_value<T, I, O, E, P>(tag_name: T, parser: P) -> impl FnMut(I) -> nom::IResult<I, O, E>
@@ -95,14 +158,26 @@ where
 {
     let tag = tag(tag_name);
     let prefix = pair(tag, multispace1);
-    let prefixed_value = preceded(prefix, parser);
-    prefixed_value
+    preceded(prefix, parser)
 }
 
-pub fn syntax_tree<I>(input: I) -> nom::IResult<I, syntax_tree::SyntaxTree>
+pub fn keyword<I>(word: &'static str) -> impl FnMut(I) -> nom::IResult<I, I, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::InputIter
+        + nom::InputTake,
+    <I as nom::InputIter>::Item: nom::AsChar,
+{
+    let boundary = not(satisfy(keywords::is_identifier_char));
+    terminated(tag(word), boundary)
+}
+
+pub fn statement<'src, I>(input: I) -> nom::IResult<I, syntax_tree::Statement<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::Range<usize>>
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -113,20 +188,60 @@ where
         + nom::InputLength,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
     <I as nom::InputIter>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
-    let eof = pair(multispace0, eof);
-    let statements = delimited(multispace0, many0(statement), eof);
-    let mut tree = map(statements, |statements| syntax_tree::SyntaxTree {
-        statements,
+    let resource = map(resource_data, syntax_tree::Statement::Resource);
+    let use_ = map(use_data, syntax_tree::Statement::Use);
+    let type_ = map(type_data, syntax_tree::Statement::Type);
+    let statement_alt = alt((resource, use_, type_));
+
+    let doc_line = map(doc_comment_line, |line| (Some(line), None));
+    let annotation = map(annotation, |annotation| (None, Some(annotation)));
+    let prelude = many0(terminated(alt((doc_line, annotation)), multispace0));
+
+    let mut statement = map(pair(prelude, statement_alt), |(prelude, statement)| {
+        let (doc_lines, annotations): (Vec<_>, Vec<_>) = prelude.into_iter().unzip();
+        let doc_lines = doc_lines.into_iter().flatten().collect::<Vec<&str>>();
+        let doc = match doc_lines.as_slice() {
+            [] => None,
+            [line] => Some(Cow::Borrowed(*line)),
+            lines => Some(Cow::Owned(lines.join("\n"))),
+        };
+        statement
+            .with_doc(doc)
+            .with_annotations(annotations.into_iter().flatten().collect())
     });
-    tree(input)
+
+    statement(input)
 }
 
-pub fn statement<I>(input: I) -> nom::IResult<I, syntax_tree::Statement>
+pub fn doc_comment_line<'src, I>(input: I) -> nom::IResult<I, &'src str, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputLength,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let text = preceded(tag("///"), not_line_ending);
+    let mut line = map(text, |text: I| {
+        let text: &str = text.into();
+        text.strip_prefix(' ').unwrap_or(text).trim_end()
+    });
+    line(input)
+}
+
+pub fn annotation<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Annotation<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::Range<usize>>
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -137,18 +252,26 @@ where
         + nom::InputLength,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
     <I as nom::InputIter>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
-    let resource = map(resource_data, |data| syntax_tree::Statement::Resource(data));
-    let mut statement_alt = alt((resource,));
+    let argument_separator = tuple((multispace0, char(','), multispace0));
+    let argument_list = separated_list0(argument_separator, expression);
+    let opening_parenthesis = pair(char('('), multispace0);
+    let closing_parenthesis = pair(multispace0, char(')'));
+    let arguments = delimited(opening_parenthesis, argument_list, closing_parenthesis);
 
-    statement_alt(input)
+    let raw_data = preceded(char('@'), pair(name_identifier, opt(arguments)));
+    let mut data = map(raw_data, |(name, arguments)| syntax_tree::Annotation {
+        name,
+        arguments: arguments.unwrap_or_default(),
+    });
+    data(input)
 }
 
-pub fn resource_data<I>(input: I) -> nom::IResult<I, syntax_tree::ResourceData>
+pub fn use_data<'src, I>(input: I) -> nom::IResult<I, syntax_tree::UseData<'src>, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::Range<usize>>
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -159,19 +282,101 @@ where
         + nom::InputLength,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
     <I as nom::InputIter>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
+{
+    let use_path = terminated(tagged_value("use", reference_path), statement_termination);
+    let mut use_data = map(use_path, |path| syntax_tree::UseData {
+        path,
+        annotations: Vec::new(),
+        span: syntax_tree::Span::default(),
+    });
+    use_data(input)
+}
+
+/// A type declaration, either naming another type as in
+/// `type region of string;` or listing object fields as in
+/// `type credentials { username = string, password = string }`.
+pub fn type_data<'src, I>(input: I) -> nom::IResult<I, syntax_tree::TypeData<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let type_name = tagged_value("type", name_identifier);
+
+    let alias_type_name = preceded(multispace1, tagged_value("of", reference_path));
+    let alias = map(
+        terminated(alias_type_name, statement_termination),
+        syntax_tree::TypeDefinition::Alias,
+    );
+    let fields = key_value_pairs(name_identifier, reference_path);
+    let object = map(code_block(fields), syntax_tree::TypeDefinition::Object);
+
+    let mut type_data = map(
+        pair(type_name, alt((alias, object))),
+        |(name, definition)| syntax_tree::TypeData {
+            type_name: name.value,
+            definition,
+            doc: None,
+            annotations: Vec::new(),
+            span: syntax_tree::Span::default(),
+        },
+    );
+
+    type_data(input)
+}
+
+pub fn resource_data<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let as_custom = resource_data_as_custom;
     let as_provider = resource_data_as_provider;
     let as_variable = resource_data_as_variable;
-    let mut alt = alt((as_custom, as_provider, as_variable));
+    let as_output = resource_data_as_output;
+    let as_module = resource_data_as_module;
+    let as_data = resource_data_as_data;
+    let mut alt = alt((
+        as_custom,
+        as_provider,
+        as_variable,
+        as_output,
+        as_module,
+        as_data,
+    ));
     alt(input)
 }
 
-pub fn resource_data_as_custom<I>(input: I) -> nom::IResult<I, syntax_tree::ResourceData>
+pub fn resource_data_as_custom<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::Range<usize>>
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -182,14 +387,13 @@ where
         + nom::InputLength,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
     <I as nom::InputIter>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
     let resource_name = terminated(tagged_value("resource", name_identifier), multispace1);
     let resource_type_name = tagged_value("of", reference_path);
     let resource_modifiers = preceded(multispace1, resource_modifier_list);
 
-    let resource_body_hashmap = key_value_pairs(name_identifier, expression);
-    let resource_body = map(code_block(resource_body_hashmap), |data| Some(data));
+    let resource_body = map(code_block(body_entries), Some);
     let resource_body_option = alt((value(None, statement_termination), resource_body));
 
     let resource_raw_data = tuple((
@@ -199,6 +403,7 @@ where
         resource_body_option,
     ));
     let mut resource_data = map(resource_raw_data, |(name, type_name, modifiers, body)| {
+        let (body, blocks) = body.unzip();
         syntax_tree::ResourceData::new(
             name,
             type_name,
@@ -206,15 +411,64 @@ where
             syntax_tree::ResourceClass::Custom,
             &modifiers,
         )
+        .with_blocks(blocks.unwrap_or_default())
+    });
+
+    resource_data(input)
+}
+
+pub fn resource_data_as_data<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let resource_name = terminated(tagged_value("data", name_identifier), multispace1);
+    let resource_type_name = tagged_value("of", reference_path);
+    let resource_modifiers = preceded(multispace1, resource_modifier_list);
+
+    let resource_body = map(code_block(body_entries), Some);
+    let resource_body_option = alt((value(None, statement_termination), resource_body));
+
+    let resource_raw_data = tuple((
+        resource_name,
+        resource_type_name,
+        resource_modifiers,
+        resource_body_option,
+    ));
+    let mut resource_data = map(resource_raw_data, |(name, type_name, modifiers, body)| {
+        let (body, blocks) = body.unzip();
+        syntax_tree::ResourceData::new(
+            name,
+            type_name,
+            body,
+            syntax_tree::ResourceClass::Data,
+            &modifiers,
+        )
+        .with_blocks(blocks.unwrap_or_default())
     });
 
     resource_data(input)
 }
 
-pub fn resource_data_as_provider<I>(input: I) -> nom::IResult<I, syntax_tree::ResourceData>
+pub fn resource_data_as_provider<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::Range<usize>>
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -225,14 +479,13 @@ where
         + nom::InputLength,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
     <I as nom::InputIter>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
     let resource_name = terminated(tagged_value("provider", name_identifier), multispace1);
     let resource_type_name = tagged_value("of", reference_path);
     let resource_modifiers = preceded(multispace1, resource_modifier_list);
 
-    let resource_body_hashmap = key_value_pairs(name_identifier, expression);
-    let resource_body = map(code_block(resource_body_hashmap), |data| Some(data));
+    let resource_body = map(code_block(body_entries), Some);
     let resource_body_option = alt((value(None, statement_termination), resource_body));
 
     let resource_raw_data = tuple((
@@ -242,22 +495,26 @@ where
         resource_body_option,
     ));
     let mut resource_data = map(resource_raw_data, |(name, type_name, modifiers, body)| {
+        let (body, blocks) = body.unzip();
         syntax_tree::ResourceData::new(
             name,
             type_name,
             body,
-            syntax_tree::ResourceClass::Custom,
+            syntax_tree::ResourceClass::Provider,
             &modifiers,
         )
+        .with_blocks(blocks.unwrap_or_default())
     });
 
     resource_data(input)
 }
 
-pub fn resource_data_as_variable<I>(input: I) -> nom::IResult<I, syntax_tree::ResourceData>
+pub fn resource_data_as_variable<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::Range<usize>>
         + nom::Slice<std::ops::RangeFrom<usize>>
@@ -268,34 +525,126 @@ where
         + nom::InputLength,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
     <I as nom::InputIter>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
-    let resource_name = terminated(tagged_value("variable", name_identifier), multispace1);
+    let resource_name = tagged_value("variable", name_identifier);
+    let resource_type_name = opt(preceded(multispace1, tagged_value("of", reference_path)));
 
-    let resource_body_hashmap = key_value_pairs(name_identifier, expression);
-    let resource_body = map(code_block(resource_body_hashmap), |data| Some(data));
+    let resource_body = map(code_block(body_entries), Some);
+    let resource_body_option = alt((value(None, statement_termination), resource_body));
+
+    let resource_raw_data = tuple((resource_name, resource_type_name, resource_body_option));
+    let mut resource_data = map(resource_raw_data, |(name, type_name, body)| {
+        let (body, blocks) = body.unzip();
+        syntax_tree::ResourceData::new(
+            name,
+            type_name.unwrap_or(ReferencePath {
+                sequence: Vec::new(),
+            }),
+            body,
+            syntax_tree::ResourceClass::Variable,
+            &Vec::new(),
+        )
+        .with_blocks(blocks.unwrap_or_default())
+    });
+
+    resource_data(input)
+}
+
+pub fn resource_data_as_output<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let resource_name = terminated(tagged_value("output", name_identifier), multispace1);
+
+    let resource_body = map(code_block(body_entries), Some);
     let resource_body_option = alt((value(None, statement_termination), resource_body));
 
     let resource_raw_data = tuple((resource_name, resource_body_option));
     let mut resource_data = map(resource_raw_data, |(name, body)| {
+        let (body, blocks) = body.unzip();
         syntax_tree::ResourceData::new(
             name,
             ReferencePath {
                 sequence: Vec::new(),
             },
             body,
-            syntax_tree::ResourceClass::Variable,
+            syntax_tree::ResourceClass::Output,
             &Vec::new(),
         )
+        .with_blocks(blocks.unwrap_or_default())
     });
 
     resource_data(input)
 }
 
-pub fn resource_modifier_list<I>(input: I) -> nom::IResult<I, Vec<syntax_tree::ResourceModifier>>
+pub fn resource_data_as_module<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ResourceData<'src>, SyntaxError<I>>
 where
     I: Clone
+        + nom::Offset
         + nom::Compare<&'static str>
+        + nom::Slice<std::ops::Range<usize>>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTake
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let resource_name = terminated(tagged_value("module", name_identifier), multispace1);
+    let resource_type_name = tagged_value("of", reference_path);
+    let resource_modifiers = preceded(multispace1, resource_modifier_list);
+
+    let resource_body = map(code_block(body_entries), Some);
+    let resource_body_option = alt((value(None, statement_termination), resource_body));
+
+    let resource_raw_data = tuple((
+        resource_name,
+        resource_type_name,
+        resource_modifiers,
+        resource_body_option,
+    ));
+    let mut resource_data = map(resource_raw_data, |(name, type_name, modifiers, body)| {
+        let (body, blocks) = body.unzip();
+        syntax_tree::ResourceData::new(
+            name,
+            type_name,
+            body,
+            syntax_tree::ResourceClass::Module,
+            &modifiers,
+        )
+        .with_blocks(blocks.unwrap_or_default())
+    });
+
+    resource_data(input)
+}
+
+pub fn resource_modifier_list<I>(
+    input: I,
+) -> nom::IResult<I, Vec<syntax_tree::ResourceModifier>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
         + nom::InputTake
         + nom::InputIter
         + nom::InputTakeAtPosition
@@ -303,7 +652,7 @@ where
     <I as nom::InputIter>::Item: nom::AsChar,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
 {
-    let scoped_tag = tag("scoped");
+    let scoped_tag = keyword("scoped");
     let scoped_item = value(syntax_tree::ResourceModifier::Scoped, scoped_tag);
 
     let resource_modifier_alt = alt((scoped_item,));
@@ -312,75 +661,214 @@ where
     resource_modifier_list(input)
 }
 
-pub fn expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+pub fn expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
         + nom::InputTakeAtPosition
         + nom::InputTake
         + nom::InputLength,
     <I as nom::InputIter>::Item: nom::AsChar,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
-    let mut expression_alt = alt((literal_expression, object_path_expression));
+    let mut expression_alt = alt((
+        literal_expression,
+        list_expression,
+        call_expression,
+        object_path_expression,
+    ));
     expression_alt(input)
 }
 
-pub fn literal_expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+/// Parses the comma separated arguments of a list or a call, allowing a
+/// trailing comma.
+pub fn expression_list<'src, I>(
+    input: I,
+) -> nom::IResult<I, Vec<syntax_tree::Expression<'src>>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputTake
+        + nom::InputLength,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let item_separator = tuple((multispace0, char(','), multispace0));
+    let items = separated_list0(item_separator, expression);
+    let trailing_comma = opt(pair(multispace0, char(',')));
+    let mut list = delimited(multispace0, terminated(items, trailing_comma), multispace0);
+    list(input)
+}
+
+pub fn list_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
     I: Clone
-        + std::borrow::Borrow<str>
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
         + nom::InputTakeAtPosition
         + nom::InputTake
         + nom::InputLength,
     <I as nom::InputIter>::Item: nom::AsChar,
     <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
+{
+    let items = delimited(char('['), expression_list, char(']'));
+    map(items, syntax_tree::Expression::List)(input)
+}
+
+pub fn call_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputTake
+        + nom::InputLength,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let function = map(identifier_value, |value| syntax_tree::NameIdentifier {
+        value: Cow::Borrowed(value),
+    });
+    let arguments = delimited(char('('), expression_list, char(')'));
+    let mut call = map(pair(function, arguments), |(function, arguments)| {
+        syntax_tree::Expression::Call(syntax_tree::FunctionCall {
+            function,
+            arguments,
+        })
+    });
+    call(input)
+}
+
+pub fn literal_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputTake
+        + nom::InputLength,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let boolean = boolean_literal_expression;
     let integer_number = integer_number_literal_expression;
     let float_number = float_number_literal_expression;
     let string = string_literal_expression;
-    let mut expression_alt = alt((boolean, string, integer_number, float_number));
+    // Floats are tried first: the integer parser would take the `1` of `1.5`
+    // and leave `.5` behind.
+    let mut expression_alt = alt((boolean, string, float_number, integer_number));
     expression_alt(input)
 }
 
-pub fn string_literal_expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+pub fn string_literal_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
-    I: Clone + nom::Slice<std::ops::RangeFrom<usize>> + nom::InputIter + nom::InputLength,
+    I: Clone
+        + nom::Offset
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputLength,
     <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let mapper = |x| syntax_tree::Expression::Literal(syntax_tree::Literal::String(x));
     map(string_literal_data, mapper)(input)
 }
 
-pub fn string_literal_data<I>(input: I) -> nom::IResult<I, String>
+/// Parses a quoted string, borrowing it from the input unless it contains
+/// escape sequences. See [`unescape`] for the sequences allowed.
+pub fn string_literal_data<'src, I>(input: I) -> nom::IResult<I, Cow<'src, str>, SyntaxError<I>>
 where
-    I: Clone + nom::Slice<std::ops::RangeFrom<usize>> + nom::InputIter + nom::InputLength,
+    I: Clone
+        + nom::Offset
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputLength,
     <I as nom::InputIter>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
-    let characters = many0(satisfy(is_valid_singleline_string_char));
+    let plain_character = value((), satisfy(is_valid_singleline_string_char));
+    let escaped_character = value((), preceded(char('\\'), satisfy(is_escapable_char)));
+    let characters = recognize(many0_count(alt((plain_character, escaped_character))));
     let raw_value = delimited(quote, characters, quote);
-    let mut value_map = map(raw_value, |chars| chars.iter().collect());
+    let mut value_map = map(raw_value, |raw: I| unescape(raw.into()));
     value_map(input)
 }
 
 pub fn is_valid_singleline_string_char(c: char) -> bool {
-    c != '"' && c != '\'' && c != '\n'
+    c != '"' && c != '\'' && c != '\n' && c != '\\'
+}
+
+pub fn is_escapable_char(c: char) -> bool {
+    matches!(c, '"' | '\'' | '\\' | 'n' | 'r' | 't')
 }
 
-pub fn integer_number_literal_expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+/// Replaces the escape sequences of a string literal: `\n`, `\r` and `\t`
+/// are a newline, a carriage return and a tab, and `\"`, `\'` and `\\` the
+/// character itself. Other sequences are rejected by the string parser.
+pub fn unescape(raw: &str) -> Cow<'_, str> {
+    if !raw.contains('\\') {
+        return Cow::Borrowed(raw);
+    }
+
+    let mut unescaped = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('t') => unescaped.push('\t'),
+                Some(escaped) => unescaped.push(escaped),
+                None => unescaped.push('\\'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+    Cow::Owned(unescaped)
+}
+
+pub fn integer_number_literal_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
     I: nom::InputTakeAtPosition,
     <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
     let mapper = |x| {
         syntax_tree::Expression::Literal(syntax_tree::Literal::Number(
@@ -390,23 +878,30 @@ where
     map(integer_number_literal_data, mapper)(input)
 }
 
-pub fn integer_number_literal_data<I>(input: I) -> nom::IResult<I, String>
+pub fn integer_number_literal_data<'src, I>(
+    input: I,
+) -> nom::IResult<I, Cow<'src, str>, SyntaxError<I>>
 where
     I: nom::InputTakeAtPosition,
     <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
-    String: std::convert::From<I>,
+    &'src str: std::convert::From<I>,
 {
-    map(digit1, |s| String::from(s))(input)
+    map(digit1, |s: I| Cow::Borrowed(s.into()))(input)
 }
 
-pub fn float_number_literal_expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+pub fn float_number_literal_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
-    I: std::borrow::Borrow<str>
+    I: Clone
+        + nom::Offset
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
         + nom::InputTakeAtPosition,
     <I as nom::InputIter>::Item: nom::AsChar,
     <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let mapper = |x| {
         syntax_tree::Expression::Literal(syntax_tree::Literal::Number(
@@ -416,35 +911,46 @@ where
     map(float_number_literal_data, mapper)(input)
 }
 
-pub fn float_number_literal_data<I>(input: I) -> nom::IResult<I, String>
+pub fn float_number_literal_data<'src, I>(
+    input: I,
+) -> nom::IResult<I, Cow<'src, str>, SyntaxError<I>>
 where
-    I: std::borrow::Borrow<str>
+    I: Clone
+        + nom::Offset
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
         + nom::InputTakeAtPosition,
     <I as nom::InputIter>::Item: nom::AsChar,
     <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
-    let digits = separated_pair(digit1, char('.'), digit1);
-    let mut data = map(digits, |(integer_part, fractional_part)| {
-        [integer_part, fractional_part].join(".")
-    });
+    let digits = recognize(separated_pair(digit1, char('.'), digit1));
+    let mut data = map(digits, |s: I| Cow::Borrowed(s.into()));
     data(input)
 }
 
-pub fn boolean_literal_expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+pub fn boolean_literal_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
-    I: Clone + nom::Compare<&'static str> + nom::InputTakeAtPosition + nom::InputTake,
-    <I as nom::InputTakeAtPosition>::Item: Clone + nom::AsChar,
+    I: Clone
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::InputIter
+        + nom::InputTake,
+    <I as nom::InputIter>::Item: nom::AsChar,
 {
-    let data = alt((value(true, tag("true")), value(false, tag("false"))));
+    let true_keyword = value(true, keyword("true"));
+    let false_keyword = value(false, keyword("false"));
+    let data = alt((true_keyword, false_keyword));
     let mut expression = map(data, |data| {
         syntax_tree::Expression::Literal(syntax_tree::Literal::Boolean(data))
     });
     expression(input)
 }
 
-pub fn quote<I>(input: I) -> nom::IResult<I, char>
+pub fn quote<I>(input: I) -> nom::IResult<I, char, SyntaxError<I>>
 where
     I: Clone + nom::Slice<std::ops::RangeFrom<usize>> + nom::InputIter,
     <I as nom::InputIter>::Item: nom::AsChar,
@@ -452,15 +958,22 @@ where
     alt((char('"'), char('\'')))(input)
 }
 
-pub fn reference_path<'a, I>(input: I) -> nom::IResult<I, syntax_tree::ReferencePath>
+pub fn reference_path<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ReferencePath<'src>, SyntaxError<I>>
 where
     I: Clone
+        + nom::Offset
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
+        + nom::InputTakeAtPosition
         + nom::InputLength
-        + nom::Compare<&'a str>
+        + nom::Compare<&'static str>
         + nom::InputTake,
     <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let name_separator = pair(char(':'), char(':'));
     let name_sequence = separated_list1(name_separator, reference);
@@ -470,70 +983,157 @@ where
     path_map(input)
 }
 
-pub fn name_identifier<I>(input: I) -> nom::IResult<I, syntax_tree::NameIdentifier>
+pub fn name_identifier<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::NameIdentifier<'src>, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputLength
+        + nom::InputTake,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let raw = preceded(tag(keywords::RAW_IDENTIFIER_PREFIX), identifier_value);
+    let mut data = map(alt((raw, plain_identifier_value)), |value| {
+        syntax_tree::NameIdentifier {
+            value: Cow::Borrowed(value),
+        }
+    });
+    data(input)
+}
+
+pub fn plain_identifier_value<'src, I>(input: I) -> nom::IResult<I, &'src str, SyntaxError<I>>
+where
+    I: Clone
+        + nom::Offset
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
+    <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
+{
+    let (rest, value) = identifier_value(input.clone())?;
+    match keywords::is_keyword(value) {
+        true => Err(nom::Err::Failure(SyntaxError {
+            input,
+            kind: SyntaxErrorKind::ReservedKeyword(value.to_string()),
+        })),
+        false => Ok((rest, value)),
+    }
+}
+
+pub fn identifier_value<'src, I>(input: I) -> nom::IResult<I, &'src str, SyntaxError<I>>
 where
-    I: Clone + nom::Slice<std::ops::RangeFrom<usize>> + nom::InputIter + nom::InputLength,
+    I: Clone
+        + nom::Offset
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputLength,
     <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let first = satisfy(|c| c == '_' || c.is_alphabetic());
-    let rest = many0(satisfy(|c| c == '_' || c.is_alphanumeric()));
-    let pair = pair(first, rest);
-    let mut data = map(pair, |(first, rest)| syntax_tree::NameIdentifier {
-        value: [vec![first], rest].iter().flatten().collect(),
+    let rest = take_while(|c: <I as nom::InputTakeAtPosition>::Item| {
+        keywords::is_identifier_char(nom::AsChar::as_char(c))
     });
+    let mut data = map(recognize(pair(first, rest)), |value: I| value.into());
     data(input)
 }
 
-pub fn reference<'a, I>(input: I) -> nom::IResult<I, syntax_tree::Reference>
+pub fn reference<'src, I>(input: I) -> nom::IResult<I, syntax_tree::Reference<'src>, SyntaxError<I>>
 where
     I: Clone
+        + nom::Offset
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
+        + nom::InputTakeAtPosition
         + nom::InputLength
-        + nom::Compare<&'a str>
+        + nom::Compare<&'static str>
         + nom::InputTake,
     <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     use syntax_tree::Reference::*;
     let from_name = reference_from_name;
-    let from_super = value(Super, tag("super"));
-    let from_land = value(Land, tag("land"));
+    let from_super = value(Super, keyword("super"));
+    let from_land = value(Land, keyword("land"));
     let mut reference_alt = alt((from_super, from_land, from_name));
     reference_alt(input)
 }
 
-pub fn reference_from_name<I>(input: I) -> nom::IResult<I, syntax_tree::Reference>
+pub fn reference_from_name<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Reference<'src>, SyntaxError<I>>
 where
-    I: Clone + nom::Slice<std::ops::RangeFrom<usize>> + nom::InputIter + nom::InputLength,
+    I: Clone
+        + nom::Offset
+        + nom::Compare<&'static str>
+        + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
+        + nom::InputIter
+        + nom::InputTakeAtPosition
+        + nom::InputLength
+        + nom::InputTake,
     <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
-    let mut reference = map(name_identifier, |name| syntax_tree::Reference::Name(name));
+    let mut reference = map(name_identifier, syntax_tree::Reference::Name);
     reference(input)
 }
 
-pub fn object_path_expression<I>(input: I) -> nom::IResult<I, syntax_tree::Expression>
+pub fn object_path_expression<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::Expression<'src>, SyntaxError<I>>
 where
     I: Clone
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
+        + nom::InputTakeAtPosition
         + nom::InputLength
         + nom::InputTake,
     <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let mapper = |op| syntax_tree::Expression::Object(op);
     map(object_path, mapper)(input)
 }
 
-pub fn object_path<I>(input: I) -> nom::IResult<I, syntax_tree::ObjectPath>
+pub fn object_path<'src, I>(
+    input: I,
+) -> nom::IResult<I, syntax_tree::ObjectPath<'src>, SyntaxError<I>>
 where
     I: Clone
+        + nom::Offset
         + nom::Compare<&'static str>
         + nom::Slice<std::ops::RangeFrom<usize>>
+        + nom::Slice<std::ops::RangeTo<usize>>
         + nom::InputIter
+        + nom::InputTakeAtPosition
         + nom::InputLength
         + nom::InputTake,
     <I as nom::InputIter>::Item: nom::AsChar,
+    <I as nom::InputTakeAtPosition>::Item: nom::AsChar,
+    &'src str: std::convert::From<I>,
 {
     let name_separator = char('.');
     let name_sequence = separated_list0(&name_separator, name_identifier);
@@ -545,3 +1145,45 @@ where
     });
     data(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::syntax_tree::{Expression, Literal, Number};
+
+    #[test]
+    fn unescapes_strings() {
+        assert_eq!(unescape(r#"a\tb\nc\rd"#), "a\tb\nc\rd");
+        assert_eq!(unescape(r#"\"q\" \'s\' \\"#), "\"q\" 's' \\");
+        assert!(matches!(unescape("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn parses_annotations_before_types() {
+        let annotated = statement::<&str>("@owner(\"team\") type creds {\n  user = string\n}");
+        let annotations = match annotated {
+            Ok(("", syntax_tree::Statement::Type(data))) => data.annotations,
+            _ => panic!("annotated type declaration not parsed"),
+        };
+        let names = annotations.iter().map(|x| x.name.value.as_ref());
+        assert_eq!(names.collect::<Vec<_>>(), ["owner"]);
+        assert_eq!(annotations[0].string_argument(0), Some("team"));
+    }
+
+    #[test]
+    fn parses_floats_before_integers() {
+        let float = literal_expression::<&str>("1.5");
+        let integer = literal_expression::<&str>("15");
+        assert!(matches!(
+            float,
+            Ok((
+                "",
+                Expression::Literal(Literal::Number(Number::FloatingPoint(_)))
+            ))
+        ));
+        assert!(matches!(
+            integer,
+            Ok(("", Expression::Literal(Literal::Number(Number::Integer(_)))))
+        ));
+    }
+}
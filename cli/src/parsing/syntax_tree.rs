@@ -1,112 +1,336 @@
+use std::borrow::{Borrow, Cow};
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 
+/// Byte range of a node in its source text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
 #[derive(Debug)]
-pub struct SyntaxTree {
-    pub statements: Vec<Statement>,
+pub struct SyntaxTree<'src> {
+    pub statements: Vec<Statement<'src>>,
 }
 
 #[derive(Debug)]
-pub enum Statement {
-    Type(TypeData),
-    Provider(ProviderData),
-    Resource(ResourceData),
-    Variable(VariableData),
+pub enum Statement<'src> {
+    Type(TypeData<'src>),
+    Provider(ProviderData<'src>),
+    Resource(ResourceData<'src>),
+    Variable(VariableData<'src>),
+    Use(UseData<'src>),
+}
+
+impl<'src> Statement<'src> {
+    pub fn annotations(&self) -> &[Annotation<'src>] {
+        match self {
+            Self::Resource(data) => &data.annotations,
+            Self::Use(data) => &data.annotations,
+            Self::Type(data) => &data.annotations,
+            _ => &[],
+        }
+    }
+
+    /// Documentation written in `///` comments right before the statement.
+    pub fn doc(&self) -> Option<&str> {
+        match self {
+            Self::Resource(data) => data.doc.as_deref(),
+            Self::Type(data) => data.doc.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Type(data) => data.span,
+            Self::Resource(data) => data.span,
+            Self::Use(data) => data.span,
+            _ => Span::default(),
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Statement<'src> {
+        match &mut self {
+            Self::Type(data) => data.span = span,
+            Self::Resource(data) => data.span = span,
+            Self::Use(data) => data.span = span,
+            _ => {}
+        }
+        self
+    }
+
+    pub fn with_doc(mut self, doc: Option<Cow<'src, str>>) -> Statement<'src> {
+        match &mut self {
+            Self::Resource(data) => data.doc = doc,
+            Self::Type(data) => data.doc = doc,
+            _ => {}
+        }
+        self
+    }
+
+    pub fn with_annotations(mut self, annotations: Vec<Annotation<'src>>) -> Statement<'src> {
+        match &mut self {
+            Self::Resource(data) => data.annotations = annotations,
+            Self::Use(data) => data.annotations = annotations,
+            Self::Type(data) => data.annotations = annotations,
+            _ => {}
+        }
+        self
+    }
+}
+
+/// Metadata attached to a statement that is not part of the declared
+/// infrastructure, such as `@owner("payments")` or `@lint_allow(naming)`.
+#[derive(Clone, Debug)]
+pub struct Annotation<'src> {
+    pub name: NameIdentifier<'src>,
+    pub arguments: Vec<Expression<'src>>,
+}
+
+impl<'src> Annotation<'src> {
+    pub fn string_argument(&self, index: usize) -> Option<&str> {
+        match self.arguments.get(index) {
+            Some(Expression::Literal(Literal::String(value))) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Arguments written as bare names, e.g. `naming` in `@lint_allow(naming)`.
+    pub fn name_arguments(&self) -> Vec<String> {
+        let names = self.arguments.iter().filter_map(|argument| match argument {
+            Expression::Literal(Literal::String(value)) => Some(value.to_string()),
+            Expression::Object(path) if path.member_path.is_none() => Some(path.object.to_string()),
+            _ => None,
+        });
+        names.collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Expression<'src> {
+    Literal(Literal<'src>),
+    Reference(ReferencePath<'src>),
+    Object(ObjectPath<'src>),
+    List(Vec<Expression<'src>>),
+    Call(FunctionCall<'src>),
+}
+
+impl<'src> Expression<'src> {
+    /// The expression and every expression nested in it, outermost first.
+    pub fn walk(&self) -> Vec<&Expression<'src>> {
+        let nested = match self {
+            Self::List(items) => items.as_slice(),
+            Self::Call(call) => call.arguments.as_slice(),
+            _ => &[],
+        };
+        let nested = nested.iter().flat_map(Expression::walk);
+        std::iter::once(self).chain(nested).collect()
+    }
 }
 
+/// A call of a builtin function, such as `contains(["F1", "B1"], sku)`.
 #[derive(Clone, Debug)]
-pub enum Expression {
-    Literal(Literal),
-    Reference(ReferencePath),
-    Object(ObjectPath),
+pub struct FunctionCall<'src> {
+    pub function: NameIdentifier<'src>,
+    pub arguments: Vec<Expression<'src>>,
 }
 
 #[derive(Clone, Debug)]
-pub struct ObjectPath {
-    pub object: ReferencePath,
-    pub member_path: Option<Vec<NameIdentifier>>,
+pub struct ObjectPath<'src> {
+    pub object: ReferencePath<'src>,
+    pub member_path: Option<Vec<NameIdentifier<'src>>>,
 }
 
 #[derive(Clone, Debug)]
-pub enum Literal {
+pub enum Literal<'src> {
     Boolean(bool),
-    String(String),
-    Number(Number),
+    String(Cow<'src, str>),
+    Number(Number<'src>),
 }
 
 #[derive(Clone, Debug)]
-pub enum Number {
-    Integer(String),
-    FloatingPoint(String),
+pub enum Number<'src> {
+    Integer(Cow<'src, str>),
+    FloatingPoint(Cow<'src, str>),
 }
 
 #[derive(Debug)]
-pub struct ProviderData {
-    pub provider_name: String,
+pub struct ProviderData<'src> {
+    pub provider_name: Cow<'src, str>,
 }
 
 #[derive(Debug)]
-pub struct TypeData {
-    pub type_name: String,
+pub struct TypeData<'src> {
+    pub type_name: Cow<'src, str>,
+    pub definition: TypeDefinition<'src>,
+    pub doc: Option<Cow<'src, str>>,
+    pub annotations: Vec<Annotation<'src>>,
+    pub span: Span,
 }
 
 #[derive(Debug)]
-pub struct VariableData {
-    pub variable_name: String,
-    pub type_name: ReferencePath,
+pub enum TypeDefinition<'src> {
+    Alias(ReferencePath<'src>),
+    Object(HashMap<NameIdentifier<'src>, ReferencePath<'src>>),
+}
+
+#[derive(Debug)]
+pub struct VariableData<'src> {
+    pub variable_name: Cow<'src, str>,
+    pub type_name: ReferencePath<'src>,
     pub sensitive: bool,
-    pub description: String,
+    pub description: Cow<'src, str>,
+}
+
+#[derive(Debug)]
+pub struct UseData<'src> {
+    pub path: ReferencePath<'src>,
+    pub annotations: Vec<Annotation<'src>>,
+    pub span: Span,
+}
+
+impl<'src> UseData<'src> {
+    pub fn imported_name(&self) -> Option<&NameIdentifier<'src>> {
+        match self.path.sequence.last() {
+            Some(Reference::Name(name)) => Some(name),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct ResourceData {
-    pub name: NameIdentifier,
-    pub type_name: ReferencePath,
-    pub body: Option<ResourceBody>,
+pub struct ResourceData<'src> {
+    pub name: NameIdentifier<'src>,
+    pub type_name: ReferencePath<'src>,
+    pub body: Option<ResourceBody<'src>>,
+    pub blocks: Vec<NestedBlock<'src>>,
     pub class: ResourceClass,
     pub is_scoped: bool,
+    pub annotations: Vec<Annotation<'src>>,
+    pub doc: Option<Cow<'src, str>>,
+    pub span: Span,
 }
 
-impl ResourceData {
+impl<'src> ResourceData<'src> {
     pub fn new(
-        name: NameIdentifier,
-        type_name: ReferencePath,
-        body: Option<ResourceBody>,
+        name: NameIdentifier<'src>,
+        type_name: ReferencePath<'src>,
+        body: Option<ResourceBody<'src>>,
         class: ResourceClass,
-        modifiers: &Vec<ResourceModifier>,
-    ) -> ResourceData {
+        modifiers: &[ResourceModifier],
+    ) -> ResourceData<'src> {
         ResourceData {
             name,
             type_name,
             body,
+            blocks: Vec::new(),
             class,
             is_scoped: modifiers.iter().any(|x| match x {
                 ResourceModifier::Scoped => true,
             }),
+            annotations: Vec::new(),
+            doc: None,
+            span: Span::default(),
         }
     }
+
+    /// Data sources only read existing infrastructure during planning, so
+    /// they are never created nor destroyed.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.class, ResourceClass::Data)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&Expression<'src>> {
+        self.body.as_ref().and_then(|body| body.get(name))
+    }
+
+    /// The `description` attribute when given as a string, or else the doc
+    /// comment of the declaration.
+    pub fn description(&self) -> Option<&str> {
+        match self.attribute("description") {
+            Some(Expression::Literal(Literal::String(value))) => Some(value),
+            _ => self.doc.as_deref(),
+        }
+    }
+
+    pub fn annotation(&self, name: &str) -> Option<&Annotation<'src>> {
+        self.annotations.iter().find(|x| x.name.value == name)
+    }
+
+    pub fn with_blocks(mut self, blocks: Vec<NestedBlock<'src>>) -> ResourceData<'src> {
+        self.blocks = blocks;
+        self
+    }
+
+    /// Attributes of the nested blocks, named by the path of blocks leading
+    /// to them, e.g. `validation.condition`.
+    pub fn nested_attributes(&self) -> Vec<(String, &Expression<'src>)> {
+        self.blocks
+            .iter()
+            .flat_map(NestedBlock::attributes)
+            .collect()
+    }
+
+    /// Nested blocks with the given name, in source order.
+    pub fn blocks<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'b NestedBlock<'src>> {
+        self.blocks.iter().filter(move |x| x.name.value == name)
+    }
 }
 
-pub type ResourceBody = HashMap<NameIdentifier, Expression>;
+pub type ResourceBody<'src> = HashMap<NameIdentifier<'src>, Expression<'src>>;
 
+/// A block nested in a declaration body, such as the `validation` blocks of
+/// a variable.
 #[derive(Clone, Debug)]
-pub struct ReferencePath {
-    pub sequence: Vec<Reference>,
+pub struct NestedBlock<'src> {
+    pub name: NameIdentifier<'src>,
+    pub body: ResourceBody<'src>,
+    pub blocks: Vec<NestedBlock<'src>>,
+}
+
+impl<'src> NestedBlock<'src> {
+    pub fn attribute(&self, name: &str) -> Option<&Expression<'src>> {
+        self.body.get(name)
+    }
+
+    /// Attributes of the block and of the blocks nested in it, sorted by
+    /// name and prefixed with the block name.
+    pub fn attributes(&self) -> Vec<(String, &Expression<'src>)> {
+        let mut attributes = self
+            .body
+            .iter()
+            .map(|(name, value)| (format!("{}.{name}", self.name), value))
+            .collect::<Vec<_>>();
+        attributes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for block in &self.blocks {
+            let nested = block.attributes().into_iter();
+            attributes.extend(nested.map(|(name, value)| (format!("{}.{name}", self.name), value)));
+        }
+        attributes
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReferencePath<'src> {
+    pub sequence: Vec<Reference<'src>>,
 }
 
 #[derive(Clone, Debug, Eq)]
-pub enum Reference {
-    Name(NameIdentifier),
+pub enum Reference<'src> {
+    Name(NameIdentifier<'src>),
     Super,
     Land,
 }
 
 #[derive(Clone, Debug, Eq)]
-pub struct NameIdentifier {
-    pub value: String,
+pub struct NameIdentifier<'src> {
+    pub value: Cow<'src, str>,
 }
 
-impl Hash for Reference {
+impl Hash for Reference<'_> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
             Self::Name(s) => {
@@ -119,7 +343,7 @@ impl Hash for Reference {
     }
 }
 
-impl PartialEq for Reference {
+impl PartialEq for Reference<'_> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Name(s1), Self::Name(s2)) => s1 == s2,
@@ -130,13 +354,47 @@ impl PartialEq for Reference {
     }
 }
 
-impl Hash for NameIdentifier {
+impl fmt::Display for ReferencePath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, reference) in self.sequence.iter().enumerate() {
+            if index > 0 {
+                write!(f, "::")?;
+            }
+            write!(f, "{reference}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Reference<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => write!(f, "{name}"),
+            Self::Super => write!(f, "super"),
+            Self::Land => write!(f, "land"),
+        }
+    }
+}
+
+impl fmt::Display for NameIdentifier<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Borrow<str> for NameIdentifier<'_> {
+    fn borrow(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Hash for NameIdentifier<'_> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.value.hash(state);
     }
 }
 
-impl PartialEq for NameIdentifier {
+impl PartialEq for NameIdentifier<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.value == other.value
     }
@@ -147,9 +405,230 @@ pub enum ResourceModifier {
     Scoped,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResourceClass {
     Custom,
     Variable,
     Provider,
+    Output,
+    Module,
+    Data,
+}
+
+impl fmt::Display for ResourceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            Self::Custom => "resource",
+            Self::Variable => "variable",
+            Self::Provider => "provider",
+            Self::Output => "output",
+            Self::Module => "module",
+            Self::Data => "data",
+        };
+        write!(f, "{keyword}")
+    }
+}
+
+/// Rebuilds a node with every piece of source text passed through `f`.
+///
+/// This is how a tree is moved from one source buffer to another, e.g. to
+/// reuse unchanged statements after an edit, or detached from its source
+/// altogether with [`MapText::to_owned_text`].
+pub trait MapText<'new> {
+    type Output;
+
+    fn map_text<F>(&self, f: &mut F) -> Self::Output
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>;
+
+    fn to_owned_text(&self) -> Self::Output {
+        self.map_text(&mut |text| Cow::Owned(text.to_string()))
+    }
+}
+
+impl<'new> MapText<'new> for SyntaxTree<'_> {
+    type Output = SyntaxTree<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> SyntaxTree<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        let statements = self.statements.iter().map(|x| x.map_text(f)).collect();
+        SyntaxTree { statements }
+    }
+}
+
+impl<'new> MapText<'new> for Statement<'_> {
+    type Output = Statement<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> Statement<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        match self {
+            Self::Type(data) => Statement::Type(TypeData {
+                type_name: f(&data.type_name),
+                definition: match &data.definition {
+                    TypeDefinition::Alias(path) => TypeDefinition::Alias(path.map_text(f)),
+                    TypeDefinition::Object(fields) => TypeDefinition::Object(
+                        fields
+                            .iter()
+                            .map(|(name, path)| (name.map_text(f), path.map_text(f)))
+                            .collect(),
+                    ),
+                },
+                doc: data.doc.as_ref().map(&mut *f),
+                annotations: data.annotations.iter().map(|x| x.map_text(f)).collect(),
+                span: data.span,
+            }),
+            Self::Provider(data) => Statement::Provider(ProviderData {
+                provider_name: f(&data.provider_name),
+            }),
+            Self::Resource(data) => Statement::Resource(data.map_text(f)),
+            Self::Variable(data) => Statement::Variable(VariableData {
+                variable_name: f(&data.variable_name),
+                type_name: data.type_name.map_text(f),
+                sensitive: data.sensitive,
+                description: f(&data.description),
+            }),
+            Self::Use(data) => Statement::Use(UseData {
+                path: data.path.map_text(f),
+                annotations: data.annotations.iter().map(|x| x.map_text(f)).collect(),
+                span: data.span,
+            }),
+        }
+    }
+}
+
+impl<'new> MapText<'new> for ResourceData<'_> {
+    type Output = ResourceData<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> ResourceData<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        let body = self.body.as_ref().map(|body| {
+            body.iter()
+                .map(|(key, value)| (key.map_text(f), value.map_text(f)))
+                .collect()
+        });
+        ResourceData {
+            name: self.name.map_text(f),
+            type_name: self.type_name.map_text(f),
+            body,
+            blocks: self.blocks.iter().map(|x| x.map_text(f)).collect(),
+            class: self.class,
+            is_scoped: self.is_scoped,
+            annotations: self.annotations.iter().map(|x| x.map_text(f)).collect(),
+            doc: self.doc.as_ref().map(&mut *f),
+            span: self.span,
+        }
+    }
+}
+
+impl<'new> MapText<'new> for NestedBlock<'_> {
+    type Output = NestedBlock<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> NestedBlock<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        let body = self.body.iter();
+        NestedBlock {
+            name: self.name.map_text(f),
+            body: body
+                .map(|(key, value)| (key.map_text(f), value.map_text(f)))
+                .collect(),
+            blocks: self.blocks.iter().map(|x| x.map_text(f)).collect(),
+        }
+    }
+}
+
+impl<'new> MapText<'new> for Annotation<'_> {
+    type Output = Annotation<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> Annotation<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        Annotation {
+            name: self.name.map_text(f),
+            arguments: self.arguments.iter().map(|x| x.map_text(f)).collect(),
+        }
+    }
+}
+
+impl<'new> MapText<'new> for Expression<'_> {
+    type Output = Expression<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> Expression<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        match self {
+            Self::Literal(literal) => Expression::Literal(literal.map_text(f)),
+            Self::Reference(path) => Expression::Reference(path.map_text(f)),
+            Self::Object(path) => Expression::Object(ObjectPath {
+                object: path.object.map_text(f),
+                member_path: path
+                    .member_path
+                    .as_ref()
+                    .map(|names| names.iter().map(|x| x.map_text(f)).collect()),
+            }),
+            Self::List(items) => Expression::List(items.iter().map(|x| x.map_text(f)).collect()),
+            Self::Call(call) => Expression::Call(FunctionCall {
+                function: call.function.map_text(f),
+                arguments: call.arguments.iter().map(|x| x.map_text(f)).collect(),
+            }),
+        }
+    }
+}
+
+impl<'new> MapText<'new> for Literal<'_> {
+    type Output = Literal<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> Literal<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        match self {
+            Self::Boolean(value) => Literal::Boolean(*value),
+            Self::String(value) => Literal::String(f(value)),
+            Self::Number(Number::Integer(value)) => Literal::Number(Number::Integer(f(value))),
+            Self::Number(Number::FloatingPoint(value)) => {
+                Literal::Number(Number::FloatingPoint(f(value)))
+            }
+        }
+    }
+}
+
+impl<'new> MapText<'new> for ReferencePath<'_> {
+    type Output = ReferencePath<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> ReferencePath<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        let sequence = self.sequence.iter().map(|reference| match reference {
+            Reference::Name(name) => Reference::Name(name.map_text(f)),
+            Reference::Super => Reference::Super,
+            Reference::Land => Reference::Land,
+        });
+        ReferencePath {
+            sequence: sequence.collect(),
+        }
+    }
+}
+
+impl<'new> MapText<'new> for NameIdentifier<'_> {
+    type Output = NameIdentifier<'new>;
+
+    fn map_text<F>(&self, f: &mut F) -> NameIdentifier<'new>
+    where
+        F: FnMut(&Cow<'_, str>) -> Cow<'new, str>,
+    {
+        NameIdentifier {
+            value: f(&self.value),
+        }
+    }
 }
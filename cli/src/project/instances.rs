@@ -0,0 +1,288 @@
+use super::resolve::Resolution;
+use super::{Module, Project};
+use crate::parsing::syntax_tree::{ResourceBody, ResourceClass, ResourceData, Statement};
+
+/// A module brought in by a `module` statement, such as
+/// `module shop of land::modules::webapp_stack { name = "shop" }`.
+///
+/// The statement body assigns the module inputs, which are the `variable`s
+/// declared by the instantiated module.
+#[derive(Debug)]
+pub struct ModuleInstance<'a> {
+    pub path: Vec<String>,
+    pub module: &'a Module<'a>,
+    pub declaration: &'a ResourceData<'a>,
+}
+
+impl<'a> ModuleInstance<'a> {
+    pub fn inputs(&self) -> Option<&'a ResourceBody<'a>> {
+        self.declaration.body.as_ref()
+    }
+}
+
+/// A declaration as it is deployed, namespaced under the module instances
+/// that contain it. Declarations of modules that are not instantiated live
+/// directly in the project and have an empty instance path.
+#[derive(Debug, Clone)]
+pub struct InstanceDeclaration<'a> {
+    pub instance: Vec<String>,
+    pub module: &'a Module<'a>,
+    pub data: &'a ResourceData<'a>,
+}
+
+impl<'a> InstanceDeclaration<'a> {
+    pub fn qualified_name(&self) -> String {
+        let name = std::iter::once(self.data.name.value.as_ref());
+        self.instance
+            .iter()
+            .map(String::as_str)
+            .chain(name)
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Expansion<'a> {
+    pub instances: Vec<ModuleInstance<'a>>,
+    pub declarations: Vec<InstanceDeclaration<'a>>,
+    pub errors: Vec<String>,
+}
+
+impl<'src> Project<'src> {
+    /// Modules that are the target of at least one `module` statement.
+    ///
+    /// These act as templates: their declarations are only deployed through
+    /// their instances.
+    pub fn instantiated_modules(&self) -> Vec<&Module<'_>> {
+        let mut targets: Vec<&Module> = Vec::new();
+        for module in self.modules() {
+            for data in module_statements(module) {
+                if let Ok(Resolution::Module(target)) = self.resolve(module, &data.type_name) {
+                    if !targets.iter().any(|x| x.path == target.path) {
+                        targets.push(target);
+                    }
+                }
+            }
+        }
+        targets
+    }
+
+    /// Expands every module instance of the project, namespacing the
+    /// declarations of instantiated modules under their instance.
+    pub fn expand(&self) -> Expansion<'_> {
+        let templates = self.instantiated_modules();
+        let mut expansion = Expansion::default();
+
+        for module in self.modules() {
+            if templates.iter().any(|x| x.path == module.path) {
+                continue;
+            }
+            let mut stack = vec![module.path.clone()];
+            self.expand_module(module, Vec::new(), &mut stack, &mut expansion);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        expansion.errors.retain(|error| seen.insert(error.clone()));
+        expansion
+    }
+
+    fn expand_module<'a>(
+        &'a self,
+        module: &'a Module<'a>,
+        instance: Vec<String>,
+        stack: &mut Vec<Vec<String>>,
+        expansion: &mut Expansion<'a>,
+    ) {
+        for statement in module.statements() {
+            let data = match statement {
+                Statement::Resource(data) => data,
+                _ => continue,
+            };
+
+            if !matches!(data.class, ResourceClass::Module) {
+                expansion.declarations.push(InstanceDeclaration {
+                    instance: instance.clone(),
+                    module,
+                    data,
+                });
+                continue;
+            }
+
+            let context = format!(
+                "module instance `{}` in {}",
+                data.name,
+                module.display_path()
+            );
+            let target = match self.resolve(module, &data.type_name) {
+                Ok(Resolution::Module(target)) => target,
+                Ok(Resolution::Declaration(..)) => {
+                    let message = format!("{context}: `{}` is not a module", data.type_name);
+                    expansion.errors.push(message);
+                    continue;
+                }
+                Err(error) => {
+                    expansion.errors.push(format!("{context}: {error}"));
+                    continue;
+                }
+            };
+
+            for error in check_inputs(data, target) {
+                expansion.errors.push(format!("{context}: {error}"));
+            }
+
+            if stack.contains(&target.path) {
+                let message = format!(
+                    "{context}: {} instantiates itself recursively",
+                    target.display_path()
+                );
+                expansion.errors.push(message);
+                continue;
+            }
+
+            let path = [instance.clone(), vec![data.name.to_string()]].concat();
+            expansion.instances.push(ModuleInstance {
+                path: path.clone(),
+                module: target,
+                declaration: data,
+            });
+
+            stack.push(target.path.clone());
+            self.expand_module(target, path, stack, expansion);
+            stack.pop();
+        }
+    }
+}
+
+impl<'src> Module<'src> {
+    /// Inputs of the module, i.e. its `variable` declarations.
+    pub fn inputs(&self) -> impl Iterator<Item = &ResourceData<'src>> {
+        self.statements().filter_map(|statement| match statement {
+            Statement::Resource(data) if matches!(data.class, ResourceClass::Variable) => {
+                Some(data)
+            }
+            _ => None,
+        })
+    }
+
+    /// Outputs of the module, i.e. its `output` declarations.
+    pub fn outputs(&self) -> impl Iterator<Item = &ResourceData<'src>> {
+        self.statements().filter_map(|statement| match statement {
+            Statement::Resource(data) if matches!(data.class, ResourceClass::Output) => Some(data),
+            _ => None,
+        })
+    }
+}
+
+fn module_statements<'a>(module: &'a Module) -> impl Iterator<Item = &'a ResourceData<'a>> {
+    module.statements().filter_map(|statement| match statement {
+        Statement::Resource(data) if matches!(data.class, ResourceClass::Module) => Some(data),
+        _ => None,
+    })
+}
+
+fn check_inputs(declaration: &ResourceData, target: &Module) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if let Some(body) = &declaration.body {
+        let mut names = body.keys().map(|key| &key.value).collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            if !target.inputs().any(|input| &input.name.value == name) {
+                let module = target.display_path();
+                errors.push(format!("{module} has no input `{name}`"));
+            }
+        }
+    }
+
+    for input in target.inputs() {
+        let is_assigned = declaration.attribute(&input.name.value).is_some();
+        let has_default = input.attribute("default").is_some();
+        if !is_assigned && !has_default {
+            errors.push(format!("missing required input `{}`", input.name));
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::Sources;
+
+    const DATABASE: &str = r#"
+variable server_name of string;
+variable sku of string { default = "S0" }
+
+resource server of azure::mssql_server {
+  name = server_name
+}
+"#;
+
+    fn with_expansion(source: &str, f: impl FnOnce(&Expansion)) {
+        let files = [("main.land", source), ("modules/db/main.land", DATABASE)];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        f(&project.expand());
+    }
+
+    #[test]
+    fn namespaces_declarations_under_their_instances() {
+        let source = r#"
+resource rg of azure::resource_group {}
+
+module shop of land::modules::db { server_name = "shop" }
+module blog of land::modules::db { server_name = "blog" }
+"#;
+        with_expansion(source, |expansion| {
+            assert!(expansion.errors.is_empty(), "{:?}", expansion.errors);
+            let paths = expansion.instances.iter().map(|x| x.path.join("::"));
+            assert_eq!(paths.collect::<Vec<_>>(), ["shop", "blog"]);
+            let names = expansion.declarations.iter().map(|x| x.qualified_name());
+            assert_eq!(
+                names.collect::<Vec<_>>(),
+                [
+                    "rg",
+                    "shop::server_name",
+                    "shop::sku",
+                    "shop::server",
+                    "blog::server_name",
+                    "blog::sku",
+                    "blog::server"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn checks_the_inputs_of_instances() {
+        let source = r#"
+module shop of land::modules::db { server_nam = "shop" }
+"#;
+        with_expansion(source, |expansion| {
+            assert_eq!(
+                expansion.errors,
+                [
+                    "module instance `shop` in land: land::modules::db has no input `server_nam`",
+                    "module instance `shop` in land: missing required input `server_name`"
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn reports_recursive_instances() {
+        let files = [
+            ("main.land", "module first of land::a {}\n"),
+            ("a/main.land", "module again of land::a {}\n"),
+        ];
+        let sources = Sources::from_files(&files);
+        let project = Project::parse(&sources).unwrap();
+        let expansion = project.expand();
+        assert_eq!(
+            expansion.errors,
+            ["module instance `again` in land::a: land::a instantiates itself recursively"]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+pub mod instances;
+pub mod resolve;
+
+use crate::parsing::{parse, syntax_tree};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub const SOURCE_EXTENSION: &str = "land";
+
+/// Source texts of a project, read before parsing so that syntax trees can
+/// borrow from them instead of copying every name and string.
+#[derive(Debug)]
+pub struct Sources {
+    pub root: PathBuf,
+    pub texts: Vec<SourceText>,
+}
+
+#[derive(Debug)]
+pub struct SourceText {
+    pub module_path: Vec<String>,
+    pub path: PathBuf,
+    pub text: String,
+}
+
+/// A landlord project: a tree of modules rooted at the project directory.
+///
+/// Every directory containing `.land` files is a module. The root module is
+/// the one referred to by `land::`, and a module's parent is the one referred
+/// to by `super::`.
+#[derive(Debug)]
+pub struct Project<'src> {
+    pub root: Module<'src>,
+}
+
+#[derive(Debug)]
+pub struct Module<'src> {
+    pub name: String,
+    pub path: Vec<String>,
+    pub dir: PathBuf,
+    pub files: Vec<SourceFile<'src>>,
+    pub children: Vec<Module<'src>>,
+}
+
+#[derive(Debug)]
+pub struct SourceFile<'src> {
+    pub path: PathBuf,
+    pub source: &'src str,
+    pub tree: syntax_tree::SyntaxTree<'src>,
+}
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Stream(PathBuf, std::io::Error),
+    Parse(PathBuf, String),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stream(path, error) => write!(f, "Stream Error: {}: {error}", path.display()),
+            Self::Parse(path, error) => write!(f, "Parse Error: {}: {error}", path.display()),
+        }
+    }
+}
+
+impl Sources {
+    pub fn load(dir: &Path) -> Result<Sources, Vec<ProjectError>> {
+        let mut sources = Sources {
+            root: dir.to_path_buf(),
+            texts: Vec::new(),
+        };
+        let mut errors = Vec::new();
+        sources.load_dir(Vec::new(), dir, &mut errors);
+        match errors.is_empty() {
+            true => Ok(sources),
+            false => Err(errors),
+        }
+    }
+
+    pub fn from_text(path: PathBuf, text: String) -> Sources {
+        let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let texts = vec![SourceText {
+            module_path: Vec::new(),
+            path,
+            text,
+        }];
+        Sources { root, texts }
+    }
+
+    /// Sources of a project held in memory, each file given by its path
+    /// relative to the project root, e.g. `network/main.land`.
+    #[cfg(test)]
+    pub fn from_files(files: &[(&str, &str)]) -> Sources {
+        let root = PathBuf::from("project");
+        let texts = files.iter().map(|(path, text)| {
+            let path = root.join(path);
+            let dir = path.parent().unwrap().strip_prefix(&root).unwrap();
+            let module_path = dir.iter().map(|x| x.to_string_lossy().to_string());
+            SourceText {
+                module_path: module_path.collect(),
+                path,
+                text: text.to_string(),
+            }
+        });
+        let texts = texts.collect();
+        Sources { root, texts }
+    }
+
+    fn load_dir(&mut self, module_path: Vec<String>, dir: &Path, errors: &mut Vec<ProjectError>) {
+        let mut entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect::<Vec<_>>(),
+            Err(error) => {
+                errors.push(ProjectError::Stream(dir.to_path_buf(), error));
+                return;
+            }
+        };
+        entries.sort();
+
+        for entry in entries {
+            if entry.is_dir() {
+                let child_name = match entry.file_name().and_then(|x| x.to_str()) {
+                    Some(name) if !name.starts_with('.') => name.to_string(),
+                    _ => continue,
+                };
+                let child_path = [module_path.clone(), vec![child_name]].concat();
+                self.load_dir(child_path, &entry, errors);
+            } else if entry.extension().and_then(|x| x.to_str()) == Some(SOURCE_EXTENSION) {
+                match std::fs::read_to_string(&entry) {
+                    Ok(text) => self.texts.push(SourceText {
+                        module_path: module_path.clone(),
+                        path: entry,
+                        text,
+                    }),
+                    Err(error) => errors.push(ProjectError::Stream(entry, error)),
+                }
+            }
+        }
+    }
+}
+
+impl<'src> Project<'src> {
+    pub fn parse(sources: &'src Sources) -> Result<Project<'src>, Vec<ProjectError>> {
+        let mut root = Module::new(String::new(), Vec::new(), sources.root.clone());
+        let mut errors = Vec::new();
+
+        for source in &sources.texts {
+            match parse(&source.text) {
+                Ok(tree) => {
+                    let module = root.descendant_mut(&source.module_path, &sources.root);
+                    module.files.push(SourceFile {
+                        path: source.path.clone(),
+                        source: &source.text,
+                        tree,
+                    });
+                }
+                Err(error) => errors.push(ProjectError::Parse(source.path.clone(), error)),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(Project { root }),
+            false => Err(errors),
+        }
+    }
+
+    pub fn module(&self, path: &[String]) -> Option<&Module<'src>> {
+        path.iter()
+            .try_fold(&self.root, |module, name| module.child(name))
+    }
+
+    pub fn modules(&self) -> Vec<&Module<'src>> {
+        let mut modules = vec![&self.root];
+        let mut index = 0;
+        while let Some(module) = modules.get(index) {
+            let children = module.children.iter();
+            modules.extend(children);
+            index += 1;
+        }
+        modules
+    }
+
+    /// The module a declaration is written in.
+    pub fn declaring_module(&self, data: &syntax_tree::ResourceData) -> Option<&Module<'src>> {
+        let modules = self.modules().into_iter();
+        modules
+            .into_iter()
+            .find(|module| module.source_file(data).is_some())
+    }
+}
+
+impl<'src> Module<'src> {
+    fn new(name: String, path: Vec<String>, dir: PathBuf) -> Module<'src> {
+        Module {
+            name,
+            path,
+            dir,
+            files: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn descendant_mut(&mut self, path: &[String], root_dir: &Path) -> &mut Module<'src> {
+        let (name, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return self,
+        };
+
+        let index = match self.children.iter().position(|child| &child.name == name) {
+            Some(index) => index,
+            None => {
+                let child_path = [self.path.clone(), vec![name.clone()]].concat();
+                let dir = child_path
+                    .iter()
+                    .fold(root_dir.to_path_buf(), |dir, x| dir.join(x));
+                self.children
+                    .push(Module::new(name.clone(), child_path, dir));
+                self.children.len() - 1
+            }
+        };
+        self.children[index].descendant_mut(rest, root_dir)
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Module<'src>> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = &syntax_tree::Statement<'src>> {
+        self.files
+            .iter()
+            .flat_map(|file| file.tree.statements.iter())
+    }
+
+    /// The file a declaration of this module is written in.
+    pub fn source_file(&self, data: &syntax_tree::ResourceData) -> Option<&SourceFile<'src>> {
+        self.files.iter().find(|file| {
+            let statements = file.tree.statements.iter();
+            statements
+                .filter_map(|statement| match statement {
+                    syntax_tree::Statement::Resource(x) => Some(x),
+                    _ => None,
+                })
+                .any(|x| std::ptr::eq(x, data))
+        })
+    }
+
+    /// Qualified name of the module as written in source, e.g. `land::network`.
+    pub fn display_path(&self) -> String {
+        let land = std::iter::once("land");
+        let names = self.path.iter().map(String::as_str);
+        land.chain(names).collect::<Vec<_>>().join("::")
+    }
+}
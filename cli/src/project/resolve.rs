@@ -0,0 +1,277 @@
+use super::{Module, Project};
+use crate::parsing::syntax_tree::{Reference, ReferencePath, ResourceData, Statement, TypeData};
+use std::fmt;
+
+const MAX_IMPORT_DEPTH: usize = 32;
+
+#[derive(Debug)]
+pub enum Resolution<'a> {
+    Module(&'a Module<'a>),
+    Declaration(&'a Module<'a>, &'a ResourceData<'a>),
+}
+
+#[derive(Debug)]
+pub enum ResolveError {
+    EmptyPath,
+    BeyondRoot(String),
+    MisplacedKeyword(&'static str),
+    NotAModule(String),
+    Unresolved { name: String, scope: String },
+    ImportCycle(String),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyPath => write!(f, "empty path"),
+            Self::BeyondRoot(scope) => write!(f, "`super` used in {scope}, which has no parent"),
+            Self::MisplacedKeyword(keyword) => {
+                write!(f, "`{keyword}` is only allowed at the start of a path")
+            }
+            Self::NotAModule(name) => write!(f, "`{name}` is not a module"),
+            Self::Unresolved { name, scope } => write!(f, "cannot find `{name}` in {scope}"),
+            Self::ImportCycle(name) => write!(f, "import of `{name}` refers back to itself"),
+        }
+    }
+}
+
+impl<'src> Project<'src> {
+    pub fn parent(&self, module: &Module) -> Option<&Module<'src>> {
+        match module.path.split_last() {
+            Some((_, parent_path)) => self.module(parent_path),
+            None => None,
+        }
+    }
+
+    /// Resolves a path as written inside `from`.
+    ///
+    /// A path starting with `land` is resolved from the project root, one
+    /// starting with `super` from the parent module, and any other path from
+    /// `from` itself, where names brought in by `use` are also visible.
+    pub fn resolve<'a>(
+        &'a self,
+        from: &'a Module<'a>,
+        path: &ReferencePath<'_>,
+    ) -> Result<Resolution<'a>, ResolveError> {
+        self.resolve_sequence(from, &path.sequence, 0)
+    }
+
+    fn resolve_sequence<'a>(
+        &'a self,
+        from: &'a Module<'a>,
+        sequence: &[Reference<'_>],
+        depth: usize,
+    ) -> Result<Resolution<'a>, ResolveError> {
+        if sequence.is_empty() {
+            return Err(ResolveError::EmptyPath);
+        }
+
+        let mut current = Resolution::Module(from);
+        let mut is_relative = true;
+
+        for (index, reference) in sequence.iter().enumerate() {
+            let module = match current {
+                Resolution::Module(module) => module,
+                Resolution::Declaration(_, data) => {
+                    return Err(ResolveError::NotAModule(data.name.to_string()))
+                }
+            };
+
+            current = match reference {
+                Reference::Land if index == 0 => {
+                    is_relative = false;
+                    Resolution::Module(&self.root)
+                }
+                Reference::Land => return Err(ResolveError::MisplacedKeyword("land")),
+                Reference::Super if is_relative => {
+                    let scope = module.display_path();
+                    let parent = self.parent(module).ok_or(ResolveError::BeyondRoot(scope))?;
+                    Resolution::Module(parent)
+                }
+                Reference::Super => return Err(ResolveError::MisplacedKeyword("super")),
+                Reference::Name(name) => {
+                    let with_imports = index == 0;
+                    is_relative = false;
+                    self.lookup(module, &name.value, with_imports, depth)?
+                }
+            };
+        }
+
+        Ok(current)
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        module: &'a Module<'a>,
+        name: &str,
+        with_imports: bool,
+        depth: usize,
+    ) -> Result<Resolution<'a>, ResolveError> {
+        if let Some(data) = module.declaration(name) {
+            return Ok(Resolution::Declaration(module, data));
+        }
+
+        if let Some(child) = module.child(name) {
+            return Ok(Resolution::Module(child));
+        }
+
+        if with_imports {
+            if let Some(import) = module.import(name) {
+                if depth >= MAX_IMPORT_DEPTH {
+                    return Err(ResolveError::ImportCycle(name.to_string()));
+                }
+                return self.resolve_sequence(module, &import.sequence, depth + 1);
+            }
+        }
+
+        Err(ResolveError::Unresolved {
+            name: name.to_string(),
+            scope: module.display_path(),
+        })
+    }
+
+    /// Checks that every `use` statement of the project refers to something.
+    pub fn check_imports(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for module in self.modules() {
+            for statement in module.statements() {
+                if let Statement::Use(data) = statement {
+                    if let Err(error) = self.resolve(module, &data.path) {
+                        errors.push(format!(
+                            "unresolved import `{}` in {}: {error}",
+                            data.path,
+                            module.display_path()
+                        ));
+                    }
+                }
+            }
+        }
+        errors
+    }
+}
+
+impl<'src> Module<'src> {
+    pub fn declaration(&self, name: &str) -> Option<&ResourceData<'src>> {
+        self.statements().find_map(|statement| match statement {
+            Statement::Resource(data) if data.name.value == name => Some(data),
+            _ => None,
+        })
+    }
+
+    pub fn type_declaration(&self, name: &str) -> Option<&TypeData<'src>> {
+        self.statements().find_map(|statement| match statement {
+            Statement::Type(data) if data.type_name == name => Some(data),
+            _ => None,
+        })
+    }
+
+    pub fn import(&self, name: &str) -> Option<&ReferencePath<'src>> {
+        self.statements().find_map(|statement| match statement {
+            Statement::Use(data) => match data.imported_name() {
+                Some(imported) if imported.value == name => Some(&data.path),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::parsers::reference_path;
+    use crate::project::Sources;
+
+    const FILES: &[(&str, &str)] = &[
+        ("main.land", "resource rg of azure::resource_group {}\n"),
+        (
+            "network/main.land",
+            "use land::rg;\n\nresource vnet of azure::virtual_network {}\n",
+        ),
+        (
+            "network/subnets.land",
+            "resource subnet of azure::subnet {}\n",
+        ),
+        (
+            "network/dns/main.land",
+            "use super::vnet;\n\nresource zone of azure::dns_zone {}\n",
+        ),
+    ];
+
+    /// Name of what a path written in the module at `from` resolves to.
+    fn resolve(from: &[&str], path: &str) -> Result<String, String> {
+        let sources = Sources::from_files(FILES);
+        let project = Project::parse(&sources).unwrap();
+        let from = from.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let module = project.module(&from).unwrap();
+        let (_, path) = reference_path::<&str>(path).unwrap();
+        match project.resolve(module, &path) {
+            Ok(Resolution::Module(module)) => Ok(module.display_path()),
+            Ok(Resolution::Declaration(module, data)) => {
+                Ok(format!("{} in {}", data.name, module.display_path()))
+            }
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    #[test]
+    fn resolves_declarations_of_every_file_of_a_module() {
+        let network = resolve(&["network"], "network").unwrap_err();
+        assert_eq!(network, "cannot find `network` in land::network");
+        assert_eq!(
+            resolve(&["network"], "subnet"),
+            resolve(&[], "network::subnet")
+        );
+        assert!(resolve(&["network"], "vnet").is_ok());
+    }
+
+    #[test]
+    fn resolves_land_and_super_paths() {
+        let rg = resolve(&[], "rg").unwrap();
+        assert_eq!(resolve(&["network", "dns"], "land::rg").unwrap(), rg);
+        assert_eq!(
+            resolve(&["network", "dns"], "super::super::rg").unwrap(),
+            rg
+        );
+        assert_eq!(
+            resolve(&["network", "dns"], "super::vnet"),
+            resolve(&[], "network::vnet")
+        );
+        assert_eq!(
+            resolve(&[], "super::rg").unwrap_err(),
+            "`super` used in land, which has no parent"
+        );
+        assert_eq!(
+            resolve(&["network"], "dns::super::vnet").unwrap_err(),
+            "`super` is only allowed at the start of a path"
+        );
+        assert_eq!(
+            resolve(&["network"], "dns::land").unwrap_err(),
+            "`land` is only allowed at the start of a path"
+        );
+    }
+
+    #[test]
+    fn resolves_imports_at_the_start_of_paths_only() {
+        assert_eq!(resolve(&["network"], "rg"), resolve(&[], "rg"));
+        assert_eq!(
+            resolve(&["network", "dns"], "vnet"),
+            resolve(&["network"], "vnet")
+        );
+        assert_eq!(
+            resolve(&[], "network::rg").unwrap_err(),
+            "cannot find `rg` in land::network"
+        );
+    }
+
+    #[test]
+    fn reports_import_cycles_and_unresolved_imports() {
+        let sources =
+            Sources::from_files(&[("main.land", "use b::c;\nuse c::b;\nuse land::missing;\n")]);
+        let project = Project::parse(&sources).unwrap();
+        let errors = project.check_imports();
+        assert_eq!(errors.len(), 3, "{errors:#?}");
+        assert!(errors[0].ends_with("import of `b` refers back to itself"));
+        assert!(errors[2].ends_with("cannot find `missing` in land"));
+    }
+}
@@ -0,0 +1,269 @@
+//! Names of landlord provider types and attributes in each build backend.
+//!
+//! Mappings are read from JSON, by namespace and then by backend. The table
+//! shipped with landlord is extended by the project in
+//! `.landlord/mappings.json`, whose entries take precedence:
+//!
+//! ```json
+//! {
+//!   "azure": {
+//!     "terraform": {
+//!       "provider": "azurerm",
+//!       "types": {
+//!         "linux_webapp": { "type": "azurerm_linux_web_app" },
+//!         "mssql_server": { "attributes": { "admin_user": "administrator_login" } }
+//!       }
+//!     },
+//!     "bicep": {
+//!       "attributes": { "resource_group_name": "" },
+//!       "types": {
+//!         "service_plan": {
+//!           "type": "Microsoft.Web/serverfarms",
+//!           "version": "2022-09-01",
+//!           "attributes": { "sku": "sku.name" }
+//!         }
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Other string entries of a backend, such as `provider`, are settings of
+//! the backend for the namespace.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Path of the project mappings, relative to the project root.
+pub const MAPPINGS_FILE: &str = ".landlord/mappings.json";
+
+const BUILTIN: &str = include_str!("mappings.json");
+
+pub const TERRAFORM: &str = "terraform";
+pub const BICEP: &str = "bicep";
+
+#[derive(Debug, Clone, Default)]
+pub struct Mappings {
+    /// Backends of every namespace, keyed by namespace and backend name.
+    backends: BTreeMap<(String, String), BackendMapping>,
+}
+
+/// Names of the types of a namespace, and of their attributes, in a backend.
+#[derive(Debug, Clone, Default)]
+pub struct BackendMapping {
+    pub settings: BTreeMap<String, String>,
+    /// Attributes named the same way in every type.
+    pub attributes: BTreeMap<String, String>,
+    pub types: BTreeMap<String, TypeMapping>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeMapping {
+    /// Name of the type in the backend, e.g. `azurerm_linux_web_app`.
+    pub name: Option<String>,
+    /// Version of the backend type, such as an Azure API version.
+    pub version: Option<String>,
+    pub attributes: BTreeMap<String, String>,
+    /// Values every resource of the type is given, such as the `kind` of a
+    /// web app.
+    pub fixed: BTreeMap<String, String>,
+    /// Factors attribute values are multiplied by in the backend, such as
+    /// `1073741824` for a size given in gigabytes but deployed in bytes.
+    pub factors: BTreeMap<String, String>,
+}
+
+impl Mappings {
+    /// The mappings shipped with landlord.
+    pub fn builtin() -> Mappings {
+        let mut mappings = Mappings::default();
+        if let Err(error) = mappings.merge_str(BUILTIN) {
+            panic!("invalid builtin mappings: {error}");
+        }
+        mappings
+    }
+
+    /// The builtin mappings, extended by those of the project, if any.
+    pub fn load_project(root: &Path) -> Result<Mappings, String> {
+        let mut mappings = Mappings::builtin();
+        let path = root.join(MAPPINGS_FILE);
+        if !path.is_file() {
+            return Ok(mappings);
+        }
+        let error = |message: String| format!("{}: {message}", path.display());
+        let text = std::fs::read_to_string(&path).map_err(|x| error(x.to_string()))?;
+        mappings.merge_str(&text).map_err(error)?;
+        Ok(mappings)
+    }
+
+    /// Adds the mappings of a JSON document, replacing the names it gives
+    /// again.
+    pub fn merge_str(&mut self, text: &str) -> Result<(), String> {
+        let document: Value = serde_json::from_str(text).map_err(|x| x.to_string())?;
+        let namespaces = document
+            .as_object()
+            .ok_or_else(|| String::from("expected an object of namespaces"))?;
+        for (namespace, backends) in namespaces {
+            let backends = object(backends, namespace)?;
+            for (backend, entries) in backends {
+                let context = format!("{namespace}.{backend}");
+                let key = (namespace.clone(), backend.clone());
+                let mapping = self.backends.entry(key).or_default();
+                mapping.merge(object(entries, &context)?, &context)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn backend(&self, namespace: &str, backend: &str) -> Option<&BackendMapping> {
+        self.backends
+            .get(&(namespace.to_string(), backend.to_string()))
+    }
+
+    pub fn type_mapping(&self, namespace: &str, backend: &str, name: &str) -> Option<&TypeMapping> {
+        self.backend(namespace, backend)?.types.get(name)
+    }
+
+    /// Name of an attribute of `namespace::name` in a backend, if it is
+    /// renamed there. An empty name means the backend has no such attribute.
+    pub fn attribute(
+        &self,
+        namespace: &str,
+        backend: &str,
+        name: &str,
+        attribute: &str,
+    ) -> Option<&str> {
+        let mapping = self.backend(namespace, backend)?;
+        let type_mapping = mapping.types.get(name);
+        let renamed = type_mapping.and_then(|x| x.attributes.get(attribute));
+        renamed
+            .or_else(|| mapping.attributes.get(attribute))
+            .map(String::as_str)
+    }
+
+    /// Terraform provider name behind a landlord namespace, e.g. `azurerm`
+    /// for `azure`. Namespaces without a known provider keep their name.
+    pub fn terraform_provider<'s>(&'s self, namespace: &'s str) -> &'s str {
+        let mapping = self.backend(namespace, TERRAFORM);
+        let provider = mapping.and_then(|x| x.settings.get("provider"));
+        provider.map_or(namespace, String::as_str)
+    }
+
+    /// Landlord namespace of a Terraform provider, the reverse of
+    /// [`Mappings::terraform_provider`].
+    pub fn namespace_of<'s>(&'s self, provider: &'s str) -> &'s str {
+        let namespaces = self.backends.iter();
+        let namespace = namespaces.into_iter().find(|((_, backend), mapping)| {
+            backend == TERRAFORM
+                && mapping.settings.get("provider").map(String::as_str) == Some(provider)
+        });
+        namespace.map_or(provider, |((namespace, _), _)| namespace)
+    }
+
+    /// Terraform type of a landlord resource type, e.g.
+    /// `azurerm_resource_group` for `azure::resource_group`.
+    pub fn terraform_type(&self, namespace: &str, name: &str) -> String {
+        let mapping = self.type_mapping(namespace, TERRAFORM, name);
+        match mapping.and_then(|x| x.name.as_ref()) {
+            Some(renamed) => renamed.clone(),
+            None => format!("{}_{name}", self.terraform_provider(namespace)),
+        }
+    }
+
+    /// Landlord name, within its namespace, of a Terraform type of
+    /// `provider`.
+    pub fn landlord_type(&self, provider: &str, terraform_type: &str) -> Option<String> {
+        let namespace = self.namespace_of(provider);
+        let types = self.backend(namespace, TERRAFORM).map(|x| &x.types);
+        let renamed = types
+            .into_iter()
+            .flatten()
+            .find(|(_, mapping)| mapping.name.as_deref() == Some(terraform_type));
+        if let Some((name, _)) = renamed {
+            return Some(name.clone());
+        }
+        // Types renamed in Terraform are not found under their own name.
+        let name = terraform_type.strip_prefix(provider)?.strip_prefix('_')?;
+        let mapping = self.type_mapping(namespace, TERRAFORM, name);
+        match mapping.and_then(|x| x.name.as_ref()) {
+            Some(_) => None,
+            None => Some(name.to_string()),
+        }
+    }
+
+    /// Landlord name of an attribute of a Terraform type, the reverse of
+    /// [`Mappings::attribute`].
+    pub fn landlord_attribute<'s>(
+        &'s self,
+        namespace: &str,
+        name: &str,
+        attribute: &'s str,
+    ) -> &'s str {
+        let mapping = self.type_mapping(namespace, TERRAFORM, name);
+        let attributes = mapping.map(|x| &x.attributes).into_iter().flatten();
+        let renamed = attributes.into_iter().find(|(_, to)| *to == attribute);
+        renamed.map_or(attribute, |(from, _)| from)
+    }
+}
+
+impl BackendMapping {
+    fn merge(&mut self, entries: &Map<String, Value>, context: &str) -> Result<(), String> {
+        for (key, value) in entries {
+            let context = format!("{context}.{key}");
+            match key.as_str() {
+                "attributes" => self.attributes.extend(strings(value, &context)?),
+                "types" => {
+                    for (name, entries) in object(value, &context)? {
+                        let context = format!("{context}.{name}");
+                        let mapping = self.types.entry(name.clone()).or_default();
+                        mapping.merge(object(entries, &context)?, &context)?;
+                    }
+                }
+                _ => {
+                    let value = string(value, &context)?;
+                    self.settings.insert(key.clone(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeMapping {
+    fn merge(&mut self, entries: &Map<String, Value>, context: &str) -> Result<(), String> {
+        for (key, value) in entries {
+            let context = format!("{context}.{key}");
+            match key.as_str() {
+                "type" => self.name = Some(string(value, &context)?),
+                "version" => self.version = Some(string(value, &context)?),
+                "attributes" => self.attributes.extend(strings(value, &context)?),
+                "fixed" => self.fixed.extend(strings(value, &context)?),
+                "factors" => self.factors.extend(strings(value, &context)?),
+                _ => return Err(format!("unknown entry `{context}`")),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn object<'v>(value: &'v Value, context: &str) -> Result<&'v Map<String, Value>, String> {
+    value
+        .as_object()
+        .ok_or_else(|| format!("`{context}` should be an object"))
+}
+
+fn string(value: &Value, context: &str) -> Result<String, String> {
+    value
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| format!("`{context}` should be a string"))
+}
+
+/// An object of strings, such as the attribute names of a type.
+fn strings(value: &Value, context: &str) -> Result<BTreeMap<String, String>, String> {
+    let entries = object(value, context)?.iter().map(|(key, value)| {
+        let value = string(value, &format!("{context}.{key}"))?;
+        Ok((key.clone(), value))
+    });
+    entries.collect()
+}
@@ -0,0 +1,203 @@
+//! Knowledge about the providers landlord deploys to.
+
+pub mod mappings;
+pub mod naming;
+pub mod schema;
+
+use crate::parsing::syntax_tree::{ResourceClass, ResourceData, Statement};
+
+/// How a resource refers to the `scoped` declaration it is deployed into:
+/// resources of the `children` types set `attribute` to the `value`
+/// attribute of the enclosing `parent`.
+#[derive(Debug)]
+pub struct ScopeLink {
+    pub namespace: &'static str,
+    pub parent: &'static str,
+    pub children: ScopeChildren,
+    pub attribute: &'static str,
+    pub value: &'static str,
+}
+
+#[derive(Debug)]
+pub enum ScopeChildren {
+    Only(&'static [&'static str]),
+    /// Every type of the namespace except these.
+    Except(&'static [&'static str]),
+}
+
+impl ScopeLink {
+    pub fn applies_to(&self, namespace: &str, child: &str, parent: &str) -> bool {
+        let is_child = match self.children {
+            ScopeChildren::Only(types) => types.contains(&child),
+            ScopeChildren::Except(types) => !types.contains(&child),
+        };
+        self.namespace == namespace && self.parent == parent && is_child
+    }
+}
+
+const SCOPE_LINKS: &[ScopeLink] = &[
+    ScopeLink {
+        namespace: "azure",
+        parent: "resource_group",
+        children: ScopeChildren::Except(&[
+            "key_vault_secret",
+            "mssql_database",
+            "resource_group",
+            "storage_container",
+        ]),
+        attribute: "resource_group_name",
+        value: "name",
+    },
+    ScopeLink {
+        namespace: "azure",
+        parent: "resource_group",
+        children: ScopeChildren::Except(&[
+            "key_vault_secret",
+            "mssql_database",
+            "resource_group",
+            "storage_container",
+            "subnet",
+        ]),
+        attribute: "location",
+        value: "location",
+    },
+    ScopeLink {
+        namespace: "azure",
+        parent: "service_plan",
+        children: ScopeChildren::Only(&[
+            "linux_function_app",
+            "linux_webapp",
+            "windows_function_app",
+            "windows_webapp",
+        ]),
+        attribute: "service_plan_id",
+        value: "id",
+    },
+    ScopeLink {
+        namespace: "azure",
+        parent: "mssql_server",
+        children: ScopeChildren::Only(&["mssql_database"]),
+        attribute: "server_id",
+        value: "id",
+    },
+    ScopeLink {
+        namespace: "azure",
+        parent: "virtual_network",
+        children: ScopeChildren::Only(&["subnet"]),
+        attribute: "virtual_network_name",
+        value: "name",
+    },
+    ScopeLink {
+        namespace: "azure",
+        parent: "storage_account",
+        children: ScopeChildren::Only(&["storage_container"]),
+        attribute: "storage_account_name",
+        value: "name",
+    },
+    ScopeLink {
+        namespace: "azure",
+        parent: "key_vault",
+        children: ScopeChildren::Only(&["key_vault_secret"]),
+        attribute: "key_vault_id",
+        value: "id",
+    },
+];
+
+const AZURE_RESOURCE_TYPES: &[&str] = &[
+    "application_insights",
+    "container_registry",
+    "cosmosdb_account",
+    "key_vault",
+    "key_vault_secret",
+    "kubernetes_cluster",
+    "linux_function_app",
+    "linux_webapp",
+    "log_analytics_workspace",
+    "mssql_database",
+    "mssql_server",
+    "network_security_group",
+    "public_ip",
+    "redis_cache",
+    "resource_group",
+    "service_plan",
+    "storage_account",
+    "storage_container",
+    "subnet",
+    "virtual_network",
+    "windows_function_app",
+    "windows_webapp",
+];
+
+/// Resource types offered by a provider, when landlord knows them.
+pub fn resource_types(provider: &str) -> Option<&'static [&'static str]> {
+    match provider {
+        "azure" => Some(AZURE_RESOURCE_TYPES),
+        _ => None,
+    }
+}
+
+/// Registry source of a Terraform provider, e.g. `hashicorp/azurerm`.
+pub fn terraform_source(provider: &str) -> String {
+    format!("hashicorp/{provider}")
+}
+
+/// Nested blocks a Terraform provider configuration requires, even empty.
+pub fn terraform_provider_blocks(provider: &str) -> &'static [&'static str] {
+    match provider {
+        "azurerm" => &["features"],
+        _ => &[],
+    }
+}
+
+/// The `scoped` declarations written before `data` among `statements`, the
+/// statements of its file, that it is linked to, nearest first, with the
+/// link to each of them. Only the nearest parent sets each attribute.
+pub fn scope_parents<'t, 's>(
+    statements: &'t [Statement<'s>],
+    data: &ResourceData,
+) -> Vec<(&'static ScopeLink, &'t ResourceData<'s>)> {
+    let (namespace, child) = match split_type(data) {
+        Some(split) => split,
+        None => return Vec::new(),
+    };
+    let resources = statements.iter().filter_map(|x| match x {
+        Statement::Resource(data) => Some(data),
+        _ => None,
+    });
+    let parents = resources
+        .take_while(|parent| !std::ptr::eq(*parent, data))
+        .filter(|parent| parent.is_scoped && parent.class == ResourceClass::Custom)
+        .collect::<Vec<_>>();
+
+    let mut links: Vec<(&ScopeLink, &ResourceData)> = Vec::new();
+    for parent in parents.into_iter().rev() {
+        let parent_type = match split_type(parent) {
+            Some((parent_namespace, parent_type)) if parent_namespace == namespace => parent_type,
+            _ => continue,
+        };
+        for link in scope_links(&namespace, &child, &parent_type) {
+            if !links.iter().any(|(x, _)| x.attribute == link.attribute) {
+                links.push((link, parent));
+            }
+        }
+    }
+    links
+}
+
+/// Namespace and name of a provider type, e.g. `azure` and `resource_group`.
+pub fn split_type(data: &ResourceData) -> Option<(String, String)> {
+    let type_name = data.type_name.to_string();
+    let (namespace, name) = type_name.split_once("::")?;
+    Some((namespace.to_string(), name.to_string()))
+}
+
+/// Links from a resource of type `namespace::child` to a `scoped` parent of
+/// type `namespace::parent`.
+pub fn scope_links<'a>(
+    namespace: &'a str,
+    child: &'a str,
+    parent: &'a str,
+) -> impl Iterator<Item = &'static ScopeLink> + 'a {
+    let links = SCOPE_LINKS.iter();
+    links.filter(move |link| link.applies_to(namespace, child, parent))
+}
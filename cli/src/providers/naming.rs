@@ -0,0 +1,278 @@
+//! Naming rules the cloud providers enforce on resource names.
+//!
+//! Breaking one of these rules is only reported by the provider at deploy
+//! time, so they are checked during validation instead.
+
+/// Characters allowed in a name, at its start or at its end.
+#[derive(Debug, Clone, Copy)]
+pub struct CharacterSet {
+    pub description: &'static str,
+    pub contains: fn(char) -> bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NamingRule {
+    /// Landlord resource type, e.g. `azure::storage_account`.
+    pub resource_type: &'static str,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub characters: CharacterSet,
+    pub start: Option<CharacterSet>,
+    pub end: Option<CharacterSet>,
+    /// Whether the name must be unique across every customer of the cloud,
+    /// usually because it ends up in a DNS name.
+    pub globally_unique: bool,
+}
+
+impl NamingRule {
+    /// Every rule the name breaks, described for the user. The characters
+    /// of a sensitive name are not quoted.
+    pub fn check(&self, name: &str, is_sensitive: bool) -> Vec<String> {
+        let mut problems = Vec::new();
+        let length = name.chars().count();
+        if length < self.min_length || length > self.max_length {
+            problems.push(format!(
+                "must be {} to {} characters long, but has {length}",
+                self.min_length, self.max_length
+            ));
+        }
+
+        let mut invalid = Vec::new();
+        for c in name.chars() {
+            if !(self.characters.contains)(c) && !invalid.contains(&c) {
+                invalid.push(c);
+            }
+        }
+        if !invalid.is_empty() && is_sensitive {
+            problems.push(format!("may only contain {}", self.characters.description));
+        } else if !invalid.is_empty() {
+            let invalid = invalid.iter().map(|c| format!("`{c}`")).collect::<Vec<_>>();
+            problems.push(format!(
+                "may only contain {}, but contains {}",
+                self.characters.description,
+                invalid.join(", ")
+            ));
+        }
+
+        let first = name.chars().next();
+        if let (Some(start), Some(first)) = (self.start, first) {
+            if !(start.contains)(first) {
+                problems.push(format!("must start with {}", start.description));
+            }
+        }
+        let last = name.chars().last();
+        if let (Some(end), Some(last)) = (self.end, last) {
+            if !(end.contains)(last) {
+                problems.push(format!("must end with {}", end.description));
+            }
+        }
+        problems
+    }
+}
+
+const ALPHANUMERICS: CharacterSet = CharacterSet {
+    description: "letters and digits",
+    contains: |c| c.is_ascii_alphanumeric(),
+};
+
+const LOWERCASE_ALPHANUMERICS: CharacterSet = CharacterSet {
+    description: "lowercase letters and digits",
+    contains: |c| c.is_ascii_lowercase() || c.is_ascii_digit(),
+};
+
+const LOWERCASE_ALPHANUMERICS_HYPHENS: CharacterSet = CharacterSet {
+    description: "lowercase letters, digits and hyphens",
+    contains: |c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-',
+};
+
+const ALPHANUMERICS_HYPHENS: CharacterSet = CharacterSet {
+    description: "letters, digits and hyphens",
+    contains: |c| c.is_ascii_alphanumeric() || c == '-',
+};
+
+const ALPHANUMERICS_UNDERSCORES_HYPHENS: CharacterSet = CharacterSet {
+    description: "letters, digits, underscores and hyphens",
+    contains: |c| c.is_ascii_alphanumeric() || c == '_' || c == '-',
+};
+
+const ALPHANUMERICS_UNDERSCORES_PERIODS_HYPHENS: CharacterSet = CharacterSet {
+    description: "letters, digits, underscores, periods and hyphens",
+    contains: |c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'),
+};
+
+const RESOURCE_GROUP_CHARACTERS: CharacterSet = CharacterSet {
+    description: "letters, digits, underscores, periods, hyphens and parentheses",
+    contains: |c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '(' | ')'),
+};
+
+const DATABASE_CHARACTERS: CharacterSet = CharacterSet {
+    description: "characters other than `<>*%&:\\/?`",
+    contains: |c| !matches!(c, '<' | '>' | '*' | '%' | '&' | ':' | '\\' | '/' | '?'),
+};
+
+const LETTER: CharacterSet = CharacterSet {
+    description: "a letter",
+    contains: |c| c.is_ascii_alphabetic(),
+};
+
+const LETTER_OR_DIGIT: CharacterSet = CharacterSet {
+    description: "a letter or a digit",
+    contains: |c| c.is_ascii_alphanumeric(),
+};
+
+const LOWERCASE_LETTER_OR_DIGIT: CharacterSet = CharacterSet {
+    description: "a lowercase letter or a digit",
+    contains: |c| c.is_ascii_lowercase() || c.is_ascii_digit(),
+};
+
+const LETTER_DIGIT_OR_UNDERSCORE: CharacterSet = CharacterSet {
+    description: "a letter, a digit or an underscore",
+    contains: |c| c.is_ascii_alphanumeric() || c == '_',
+};
+
+const NOT_PERIOD: CharacterSet = CharacterSet {
+    description: "a character other than a period",
+    contains: |c| c != '.',
+};
+
+const NOT_PERIOD_OR_SPACE: CharacterSet = CharacterSet {
+    description: "a character other than a period or a space",
+    contains: |c| c != '.' && c != ' ',
+};
+
+const fn rule(
+    resource_type: &'static str,
+    min_length: usize,
+    max_length: usize,
+    characters: CharacterSet,
+) -> NamingRule {
+    NamingRule {
+        resource_type,
+        min_length,
+        max_length,
+        characters,
+        start: None,
+        end: None,
+        globally_unique: false,
+    }
+}
+
+const fn web_app(resource_type: &'static str) -> NamingRule {
+    NamingRule {
+        start: Some(LETTER_OR_DIGIT),
+        end: Some(LETTER_OR_DIGIT),
+        globally_unique: true,
+        ..rule(resource_type, 2, 60, ALPHANUMERICS_HYPHENS)
+    }
+}
+
+const fn network(resource_type: &'static str, min_length: usize, max_length: usize) -> NamingRule {
+    NamingRule {
+        start: Some(LETTER_OR_DIGIT),
+        end: Some(LETTER_DIGIT_OR_UNDERSCORE),
+        ..rule(
+            resource_type,
+            min_length,
+            max_length,
+            ALPHANUMERICS_UNDERSCORES_PERIODS_HYPHENS,
+        )
+    }
+}
+
+const AZURE_RULES: &[NamingRule] = &[
+    rule("azure::application_insights", 1, 260, DATABASE_CHARACTERS),
+    NamingRule {
+        globally_unique: true,
+        ..rule("azure::container_registry", 5, 50, ALPHANUMERICS)
+    },
+    NamingRule {
+        start: Some(LOWERCASE_LETTER_OR_DIGIT),
+        globally_unique: true,
+        ..rule(
+            "azure::cosmosdb_account",
+            3,
+            44,
+            LOWERCASE_ALPHANUMERICS_HYPHENS,
+        )
+    },
+    NamingRule {
+        start: Some(LETTER),
+        end: Some(LETTER_OR_DIGIT),
+        globally_unique: true,
+        ..rule("azure::key_vault", 3, 24, ALPHANUMERICS_HYPHENS)
+    },
+    NamingRule {
+        start: Some(LETTER_OR_DIGIT),
+        end: Some(LETTER_OR_DIGIT),
+        ..rule(
+            "azure::kubernetes_cluster",
+            1,
+            63,
+            ALPHANUMERICS_UNDERSCORES_HYPHENS,
+        )
+    },
+    web_app("azure::linux_function_app"),
+    web_app("azure::linux_webapp"),
+    NamingRule {
+        start: Some(LETTER_OR_DIGIT),
+        end: Some(LETTER_OR_DIGIT),
+        ..rule(
+            "azure::log_analytics_workspace",
+            4,
+            63,
+            ALPHANUMERICS_HYPHENS,
+        )
+    },
+    NamingRule {
+        end: Some(NOT_PERIOD_OR_SPACE),
+        ..rule("azure::mssql_database", 1, 128, DATABASE_CHARACTERS)
+    },
+    NamingRule {
+        start: Some(LOWERCASE_LETTER_OR_DIGIT),
+        end: Some(LOWERCASE_LETTER_OR_DIGIT),
+        globally_unique: true,
+        ..rule(
+            "azure::mssql_server",
+            1,
+            63,
+            LOWERCASE_ALPHANUMERICS_HYPHENS,
+        )
+    },
+    network("azure::network_security_group", 1, 80),
+    network("azure::public_ip", 1, 80),
+    NamingRule {
+        start: Some(LETTER_OR_DIGIT),
+        end: Some(LETTER_OR_DIGIT),
+        globally_unique: true,
+        ..rule("azure::redis_cache", 1, 63, ALPHANUMERICS_HYPHENS)
+    },
+    NamingRule {
+        end: Some(NOT_PERIOD),
+        ..rule("azure::resource_group", 1, 90, RESOURCE_GROUP_CHARACTERS)
+    },
+    rule("azure::service_plan", 1, 60, ALPHANUMERICS_HYPHENS),
+    NamingRule {
+        globally_unique: true,
+        ..rule("azure::storage_account", 3, 24, LOWERCASE_ALPHANUMERICS)
+    },
+    NamingRule {
+        start: Some(LOWERCASE_LETTER_OR_DIGIT),
+        ..rule(
+            "azure::storage_container",
+            3,
+            63,
+            LOWERCASE_ALPHANUMERICS_HYPHENS,
+        )
+    },
+    network("azure::subnet", 1, 80),
+    network("azure::virtual_network", 2, 64),
+    web_app("azure::windows_function_app"),
+    web_app("azure::windows_webapp"),
+];
+
+/// Naming rule of a resource type such as `azure::mssql_server`, if known.
+pub fn rule_for(resource_type: &str) -> Option<&'static NamingRule> {
+    AZURE_RULES
+        .iter()
+        .find(|x| x.resource_type == resource_type)
+}
@@ -0,0 +1,449 @@
+//! Provider schemas, as printed by `terraform providers schema -json`.
+//!
+//! Schemas are loaded from JSON files and indexed by landlord resource type,
+//! so that `azure::mssql_server` finds the schema of `azurerm_mssql_server`.
+//! Attributes renamed by the Terraform mappings take their landlord names.
+
+use super::mappings::Mappings;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Directory of a project where provider schemas are looked up.
+pub const SCHEMA_DIR: &str = ".landlord/schemas";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeType {
+    String,
+    Number,
+    Bool,
+    List(Box<AttributeType>),
+    Set(Box<AttributeType>),
+    Map(Box<AttributeType>),
+    Object(BTreeMap<String, AttributeType>),
+    Tuple(Vec<AttributeType>),
+    /// Any type, decided at runtime.
+    Dynamic,
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub attribute_type: AttributeType,
+    pub description: Option<String>,
+    pub required: bool,
+    pub optional: bool,
+    pub computed: bool,
+    pub sensitive: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Block {
+    pub attributes: BTreeMap<String, Attribute>,
+    pub blocks: BTreeMap<String, NestedBlock>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NestedBlock {
+    /// How the block repeats: `single`, `list`, `set` or `map`.
+    pub nesting_mode: String,
+    pub block: Block,
+    pub min_items: usize,
+    pub max_items: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchemaKind {
+    Resource,
+    DataSource,
+}
+
+#[derive(Debug)]
+pub enum SchemaError {
+    Stream(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+    Format(PathBuf, String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stream(path, error) => write!(f, "Stream Error: {}: {error}", path.display()),
+            Self::Json(path, error) => write!(f, "Schema Error: {}: {error}", path.display()),
+            Self::Format(path, error) => write!(f, "Schema Error: {}: {error}", path.display()),
+        }
+    }
+}
+
+/// Schemas of every resource and data source type of the loaded providers,
+/// keyed by kind and landlord type, e.g. `azure::mssql_server`.
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    schemas: HashMap<(SchemaKind, String), Block>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> SchemaRegistry {
+        SchemaRegistry::default()
+    }
+
+    /// Loads every `.json` file of the project schema directory, if any.
+    pub fn load_project(
+        root: &Path,
+        mappings: &Mappings,
+    ) -> Result<SchemaRegistry, Vec<SchemaError>> {
+        let mut registry = SchemaRegistry::new();
+        let dir = root.join(SCHEMA_DIR);
+        if !dir.is_dir() {
+            return Ok(registry);
+        }
+
+        let mut files = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|x| x.to_str()) == Some("json"))
+                .collect::<Vec<_>>(),
+            Err(error) => return Err(vec![SchemaError::Stream(dir, error)]),
+        };
+        files.sort();
+
+        let errors = files
+            .iter()
+            .filter_map(|file| registry.load_file(file, mappings).err())
+            .collect::<Vec<_>>();
+        match errors.is_empty() {
+            true => Ok(registry),
+            false => Err(errors),
+        }
+    }
+
+    pub fn load_file(&mut self, path: &Path, mappings: &Mappings) -> Result<(), SchemaError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| SchemaError::Stream(path.to_path_buf(), error))?;
+        self.load_str(&text, mappings).map_err(|error| match error {
+            LoadError::Json(error) => SchemaError::Json(path.to_path_buf(), error),
+            LoadError::Format(error) => SchemaError::Format(path.to_path_buf(), error),
+        })
+    }
+
+    /// Schemas of a JSON document, with the builtin mappings.
+    #[cfg(test)]
+    pub fn from_json(text: &str) -> SchemaRegistry {
+        let mut registry = SchemaRegistry::new();
+        if registry.load_str(text, &Mappings::builtin()).is_err() {
+            panic!("invalid schema document");
+        }
+        registry
+    }
+
+    fn load_str(&mut self, text: &str, mappings: &Mappings) -> Result<(), LoadError> {
+        let document: Value = serde_json::from_str(text).map_err(LoadError::Json)?;
+        let providers = document
+            .get("provider_schemas")
+            .and_then(Value::as_object)
+            .ok_or_else(|| format_error("missing `provider_schemas` object"))?;
+
+        for (source, provider) in providers {
+            // Sources look like `registry.terraform.io/hashicorp/azurerm`.
+            let name = source.rsplit('/').next().unwrap_or(source);
+            let namespace = mappings.namespace_of(name);
+            let kinds = [
+                (SchemaKind::Resource, "resource_schemas"),
+                (SchemaKind::DataSource, "data_source_schemas"),
+            ];
+            for (kind, key) in kinds {
+                let schemas = provider.get(key).and_then(Value::as_object);
+                for (terraform_type, schema) in schemas.into_iter().flatten() {
+                    let landlord_type = match mappings.landlord_type(name, terraform_type) {
+                        Some(landlord_type) => landlord_type,
+                        None => continue,
+                    };
+                    let block = schema
+                        .get("block")
+                        .ok_or_else(|| format_error(format!("`{terraform_type}` has no block")))?;
+                    let mut block = parse_block(block)
+                        .map_err(|error| format_error(format!("`{terraform_type}`: {error}")))?;
+                    block.attributes = std::mem::take(&mut block.attributes)
+                        .into_iter()
+                        .map(|(attribute, schema)| {
+                            let renamed =
+                                mappings.landlord_attribute(namespace, &landlord_type, &attribute);
+                            (renamed.to_string(), schema)
+                        })
+                        .collect();
+                    let key = (kind, format!("{namespace}::{landlord_type}"));
+                    self.schemas.insert(key, block);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Schema of a landlord type such as `azure::mssql_server`.
+    pub fn get(&self, kind: SchemaKind, type_name: &str) -> Option<&Block> {
+        self.schemas.get(&(kind, type_name.to_string()))
+    }
+
+    /// Names of the types of `kind` known in a provider namespace, or `None`
+    /// when no schema of that namespace was loaded.
+    pub fn type_names(&self, kind: SchemaKind, namespace: &str) -> Option<Vec<&str>> {
+        let prefix = format!("{namespace}::");
+        let mut names = self
+            .schemas
+            .keys()
+            .filter(|(x, _)| *x == kind)
+            .filter_map(|(_, name)| name.strip_prefix(&prefix))
+            .collect::<Vec<_>>();
+        names.sort();
+        match names.is_empty() {
+            true => None,
+            false => Some(names),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+}
+
+enum LoadError {
+    Json(serde_json::Error),
+    Format(String),
+}
+
+fn format_error(message: impl Into<String>) -> LoadError {
+    LoadError::Format(message.into())
+}
+
+fn parse_block(value: &Value) -> Result<Block, String> {
+    let empty = Map::new();
+    let attributes = value
+        .get("attributes")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let block_types = value
+        .get("block_types")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+
+    let mut block = Block::default();
+    for (name, attribute) in attributes {
+        let attribute_type = attribute
+            .get("type")
+            .ok_or_else(|| format!("attribute `{name}` has no type"))
+            .and_then(parse_type)?;
+        let flag = |key| attribute.get(key).and_then(Value::as_bool).unwrap_or(false);
+        block.attributes.insert(
+            name.clone(),
+            Attribute {
+                attribute_type,
+                description: attribute
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                required: flag("required"),
+                optional: flag("optional"),
+                computed: flag("computed"),
+                sensitive: flag("sensitive"),
+            },
+        );
+    }
+    for (name, nested) in block_types {
+        let inner = nested
+            .get("block")
+            .ok_or_else(|| format!("block `{name}` has no block"))?;
+        let count = |key| nested.get(key).and_then(Value::as_u64).map(|x| x as usize);
+        block.blocks.insert(
+            name.clone(),
+            NestedBlock {
+                nesting_mode: nested
+                    .get("nesting_mode")
+                    .and_then(Value::as_str)
+                    .unwrap_or("single")
+                    .to_string(),
+                block: parse_block(inner)?,
+                min_items: count("min_items").unwrap_or(0),
+                max_items: count("max_items").filter(|x| *x > 0),
+            },
+        );
+    }
+    Ok(block)
+}
+
+/// Parses a type in Terraform's JSON notation: `"string"`,
+/// `["list", "string"]`, `["object", {"name": "string"}]` and so on.
+fn parse_type(value: &Value) -> Result<AttributeType, String> {
+    let invalid = || format!("invalid type `{value}`");
+    match value {
+        Value::String(name) => match name.as_str() {
+            "string" => Ok(AttributeType::String),
+            "number" => Ok(AttributeType::Number),
+            "bool" => Ok(AttributeType::Bool),
+            "dynamic" => Ok(AttributeType::Dynamic),
+            _ => Err(invalid()),
+        },
+        Value::Array(parts) => match (parts.first().and_then(Value::as_str), parts.get(1)) {
+            (Some("list"), Some(inner)) => Ok(AttributeType::List(Box::new(parse_type(inner)?))),
+            (Some("set"), Some(inner)) => Ok(AttributeType::Set(Box::new(parse_type(inner)?))),
+            (Some("map"), Some(inner)) => Ok(AttributeType::Map(Box::new(parse_type(inner)?))),
+            (Some("object"), Some(Value::Object(fields))) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, x)| Ok((name.clone(), parse_type(x)?)))
+                    .collect::<Result<_, String>>()?;
+                Ok(AttributeType::Object(fields))
+            }
+            (Some("tuple"), Some(Value::Array(items))) => {
+                let items = items.iter().map(parse_type).collect::<Result<_, _>>()?;
+                Ok(AttributeType::Tuple(items))
+            }
+            _ => Err(invalid()),
+        },
+        _ => Err(invalid()),
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String => write!(f, "string"),
+            Self::Number => write!(f, "number"),
+            Self::Bool => write!(f, "bool"),
+            Self::List(inner) => write!(f, "list({inner})"),
+            Self::Set(inner) => write!(f, "set({inner})"),
+            Self::Map(inner) => write!(f, "map({inner})"),
+            Self::Object(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, x)| format!("{name} = {x}"))
+                    .collect::<Vec<_>>();
+                write!(f, "object({{ {} }})", fields.join(", "))
+            }
+            Self::Tuple(items) => {
+                let items = items.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+                write!(f, "tuple([{}])", items.join(", "))
+            }
+            Self::Dynamic => write!(f, "any"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"{
+  "provider_schemas": {
+    "registry.terraform.io/hashicorp/azurerm": {
+      "resource_schemas": {
+        "azurerm_mssql_server": {
+          "block": {
+            "attributes": {
+              "administrator_login_password": { "type": "string", "optional": true, "sensitive": true },
+              "tags": { "type": ["map", "string"], "optional": true }
+            },
+            "block_types": {
+              "identity": {
+                "nesting_mode": "list",
+                "max_items": 1,
+                "block": {
+                  "attributes": {
+                    "identity_ids": { "type": ["set", "string"], "optional": true }
+                  }
+                }
+              }
+            }
+          }
+        },
+        "azurerm_linux_web_app": {
+          "block": { "attributes": { "name": { "type": "string", "required": true } } }
+        }
+      },
+      "data_source_schemas": {
+        "azurerm_client_config": {
+          "block": { "attributes": { "tenant_id": { "type": "string", "computed": true } } }
+        }
+      }
+    }
+  }
+}"#;
+
+    #[test]
+    fn names_types_and_attributes_as_landlord_does() {
+        let registry = SchemaRegistry::from_json(SCHEMA);
+        let server = registry
+            .get(SchemaKind::Resource, "azure::mssql_server")
+            .unwrap();
+        let password = &server.attributes["admin_password"];
+        assert!(password.sensitive && password.optional && !password.required);
+        assert_eq!(
+            server.attributes["tags"].attribute_type.to_string(),
+            "map(string)"
+        );
+
+        let identity = &server.blocks["identity"];
+        assert_eq!(
+            (identity.nesting_mode.as_str(), identity.max_items),
+            ("list", Some(1))
+        );
+        let ids = &identity.block.attributes["identity_ids"].attribute_type;
+        assert_eq!(ids.to_string(), "set(string)");
+
+        assert!(registry
+            .get(SchemaKind::Resource, "azure::linux_webapp")
+            .is_some());
+        assert!(registry
+            .get(SchemaKind::DataSource, "azure::client_config")
+            .is_some());
+        assert_eq!(
+            registry.type_names(SchemaKind::Resource, "azure"),
+            Some(vec!["linux_webapp", "mssql_server"])
+        );
+        assert_eq!(registry.type_names(SchemaKind::Resource, "aws"), None);
+    }
+
+    #[test]
+    fn parses_terraform_types() {
+        let parse = |text: &str| parse_type(&serde_json::from_str(text).unwrap());
+        assert_eq!(
+            parse(r#"["object", {"name": "string", "ports": ["list", "number"]}]"#)
+                .unwrap()
+                .to_string(),
+            "object({ name = string, ports = list(number) })"
+        );
+        assert_eq!(
+            parse(r#"["tuple", ["bool", "dynamic"]]"#)
+                .unwrap()
+                .to_string(),
+            "tuple([bool, any])"
+        );
+        assert_eq!(parse(r#""text""#).unwrap_err(), r#"invalid type `"text"`"#);
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        let mut registry = SchemaRegistry::new();
+        let mappings = Mappings::builtin();
+        let untyped = r#"{ "provider_schemas": { "azurerm": { "resource_schemas": {
+            "azurerm_mssql_server": { "block": { "attributes": { "name": {} } } }
+        } } } }"#;
+        match registry.load_str(untyped, &mappings) {
+            Err(LoadError::Format(error)) => {
+                assert_eq!(
+                    error,
+                    "`azurerm_mssql_server`: attribute `name` has no type"
+                )
+            }
+            _ => panic!("attribute without a type accepted"),
+        }
+        assert!(matches!(
+            registry.load_str("{}", &mappings),
+            Err(LoadError::Format(_))
+        ));
+        assert!(matches!(
+            registry.load_str("{", &mappings),
+            Err(LoadError::Json(_))
+        ));
+    }
+}